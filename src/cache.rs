@@ -0,0 +1,126 @@
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long cached responses are used for, unless configured otherwise.
+pub const DEFAULT_CACHE_TTL: u64 = 3600;
+
+/// How the cache is used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheMode {
+    /// Use cached responses until they expire, and cache new ones.
+    Normal,
+    /// Ignore cached responses, and cache new ones.
+    Refresh,
+    /// Only use cached responses, however old they are.
+    Offline,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    cached_at: u64,
+    response: T,
+}
+
+/// Responses cached under `<cache-dir>/gh-tf-mod`, keyed by host, org, and query.
+///
+/// The cache dir is `XDG_CACHE_HOME`, or `~/.cache`.
+pub struct Cache {
+    dir: Option<PathBuf>,
+    ttl: Duration,
+    mode: CacheMode,
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let cache_home = match env::var("XDG_CACHE_HOME") {
+        Ok(cache_home) if !cache_home.is_empty() => PathBuf::from(cache_home),
+        _ => PathBuf::from(env::var("HOME").ok()?).join(".cache"),
+    };
+    Some(cache_home.join("gh-tf-mod"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+impl Cache {
+    /// Creates a cache whose responses expire after `ttl` seconds.
+    pub fn new(ttl: u64, mode: CacheMode) -> Cache {
+        Cache {
+            dir: cache_dir(),
+            ttl: Duration::from_secs(ttl),
+            mode,
+        }
+    }
+
+    fn entry_path(&self, host: &str, org: &str, query: &str) -> Option<PathBuf> {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}\n{}\n{}", host, org, query));
+        let key = format!("{:x}", hasher.finalize());
+        Some(self.dir.as_ref()?.join(format!("{}.json", key)))
+    }
+
+    /// Returns the cached response to `query`, or fetches and caches it.
+    ///
+    /// Responses that can't be read back, e.g. because they were cached by another version, are fetched again.
+    pub fn fetch<T, F>(&self, host: &str, org: &str, query: &str, fetch: F) -> Result<T>
+    where
+        T: serde::Serialize + DeserializeOwned,
+        F: FnOnce() -> Result<T>,
+    {
+        let entry_path = self.entry_path(host, org, query);
+
+        if self.mode != CacheMode::Refresh {
+            let entry = entry_path
+                .as_ref()
+                .and_then(|entry_path| read_to_string(entry_path).ok())
+                .and_then(|entry_string| serde_json::from_str::<CacheEntry<T>>(&entry_string).ok());
+            match entry {
+                Some(entry) if self.mode == CacheMode::Offline => return Ok(entry.response),
+                Some(entry) if now().saturating_sub(entry.cached_at) < self.ttl.as_secs() => {
+                    return Ok(entry.response)
+                }
+                _ if self.mode == CacheMode::Offline => bail!(
+                    "Nothing is cached for this query. Run it once without `--offline` to cache it."
+                ),
+                _ => {}
+            }
+        }
+
+        let response = fetch()?;
+        if self.ttl.is_zero() {
+            return Ok(response);
+        }
+        if let Some(entry_path) = entry_path {
+            let entry = CacheEntry {
+                cached_at: now(),
+                response,
+            };
+            if let Err(err) = self.save(&entry_path, &entry) {
+                eprintln!(
+                    "Could not cache response in {}: {}",
+                    entry_path.to_string_lossy(),
+                    err
+                );
+            }
+            return Ok(entry.response);
+        }
+        Ok(response)
+    }
+
+    fn save<T: serde::Serialize>(&self, entry_path: &Path, entry: &CacheEntry<T>) -> Result<()> {
+        if let Some(parent_path) = entry_path.parent() {
+            create_dir_all(parent_path)?;
+        }
+        write(entry_path, serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+}
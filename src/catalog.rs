@@ -0,0 +1,183 @@
+use crate::client::Client;
+use crate::error::Error;
+use crate::gh::{get_module_files, ListModulesPages, README_FILE_NAME};
+use crate::interface::{parse_module_files, ModuleInput, ModuleInterface, ModuleOutput};
+use crate::model::{Module, ModuleList};
+use crate::naming::Naming;
+use crate::parallel::map_concurrently;
+use crate::show::DEFAULT_REF;
+use crate::templates::{Templates, CATALOG_INDEX_TEMPLATE, CATALOG_MODULE_TEMPLATE};
+use anyhow::{Context, Result};
+use serde_derive::Serialize;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+/// The name of the index page of a catalog, without its extension.
+const INDEX_PAGE: &str = "index";
+
+/// A module of a catalog, documented at its latest release.
+#[derive(Debug, Serialize)]
+pub struct CatalogModule {
+    #[serde(flatten)]
+    pub module: Module,
+    /// Tag of the latest release, if the module was released.
+    pub release_tag: Option<String>,
+    /// The ref the README and interface were read at: the tag of the latest release, or the default branch.
+    pub git_ref: String,
+    /// File name of the page of the module, relative to the index page.
+    pub page: String,
+    pub readme: Option<String>,
+    pub inputs: Vec<ModuleInput>,
+    pub outputs: Vec<ModuleOutput>,
+}
+
+/// Every module of an organization.
+#[derive(Debug, Serialize)]
+pub struct Catalog {
+    pub org: String,
+    pub modules: Vec<CatalogModule>,
+}
+
+/// A page of a single module, which can refer to the organization of the catalog.
+#[derive(Serialize)]
+struct CatalogModulePage<'a> {
+    org: &'a str,
+    #[serde(flatten)]
+    module: &'a CatalogModule,
+}
+
+fn catalog_module(
+    client: &dyn Client,
+    naming: &Naming,
+    org: &str,
+    module: Module,
+    release_tag: Option<String>,
+    extension: &str,
+) -> Result<CatalogModule> {
+    let git_ref = release_tag
+        .clone()
+        .unwrap_or_else(|| DEFAULT_REF.to_string());
+    let entries = match get_module_files(
+        client,
+        naming,
+        org.to_string(),
+        Some(module.provider.clone()),
+        module.name.clone(),
+        &git_ref,
+    ) {
+        Ok(response) => response
+            .data
+            .repository
+            .files
+            .map(|files| files.entries)
+            .unwrap_or_default(),
+        // E.g. an empty repository, which has nothing at its default branch.
+        Err(Error::NotFound(message)) => {
+            eprintln!(
+                "Warning: {}, cataloging {} without a README or interface",
+                message, module.repo
+            );
+            vec![]
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let interface = parse_module_files(&entries).unwrap_or_else(|err| {
+        eprintln!(
+            "Warning: {:#}, cataloging {} without an interface",
+            err, module.repo
+        );
+        ModuleInterface::default()
+    });
+    let readme = entries
+        .into_iter()
+        .find(|entry| entry.name == README_FILE_NAME)
+        .and_then(|entry| entry.object)
+        .and_then(|object| object.text);
+    Ok(CatalogModule {
+        page: format!("{}-{}.{}", module.provider, module.name, extension),
+        module,
+        release_tag,
+        git_ref,
+        readme,
+        inputs: interface.inputs,
+        outputs: interface.outputs,
+    })
+}
+
+/// Lists every module of `org`, following every page, and gets the README and interface of each at its latest release.
+///
+/// Modules without a release are documented at their default branch.
+/// Modules whose files can't be found, like empty repositories, or whose interface can't be parsed
+/// are still cataloged, without what is missing, and a warning is printed.
+pub fn build_catalog(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    extension: &str,
+    concurrency: usize,
+) -> Result<Catalog> {
+    let list_modules_response =
+        ListModulesPages::new(client, naming, org.clone(), provider, None, None).merge()?;
+    let release_tags: Vec<Option<String>> = list_modules_response
+        .data
+        .search
+        .nodes
+        .iter()
+        .map(|node| {
+            node.releases
+                .nodes
+                .first()
+                .map(|release| release.tag_name.clone())
+        })
+        .collect();
+    let modules: Vec<(Module, Option<String>)> = ModuleList::from(list_modules_response)
+        .modules
+        .into_iter()
+        .zip(release_tags)
+        .collect();
+    let modules = map_concurrently(&modules, concurrency, |(module, release_tag)| {
+        catalog_module(
+            client,
+            naming,
+            &org,
+            module.clone(),
+            release_tag.clone(),
+            extension,
+        )
+        .with_context(|| format!("Could not catalog {}", module.repo))
+    })
+    .into_iter()
+    .collect::<Result<Vec<CatalogModule>>>()?;
+    Ok(Catalog { org, modules })
+}
+
+/// Writes the index page of `catalog` and a page for each of its modules into `out_dir`.
+pub fn write_catalog(
+    catalog: &Catalog,
+    templates: &Templates,
+    out_dir: &Path,
+    extension: &str,
+) -> Result<()> {
+    create_dir_all(out_dir)
+        .with_context(|| format!("Could not create {}", out_dir.to_string_lossy()))?;
+    let index_path = out_dir.join(format!("{}.{}", INDEX_PAGE, extension));
+    write(
+        &index_path,
+        templates.render(CATALOG_INDEX_TEMPLATE, catalog)?,
+    )
+    .with_context(|| format!("Could not write {}", index_path.to_string_lossy()))?;
+    for module in &catalog.modules {
+        let page = CatalogModulePage {
+            org: &catalog.org,
+            module,
+        };
+        let page_path = out_dir.join(&module.page);
+        write(
+            &page_path,
+            templates.render(CATALOG_MODULE_TEMPLATE, &page)?,
+        )
+        .with_context(|| format!("Could not write {}", page_path.to_string_lossy()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,299 @@
+use crate::error::Error;
+use crate::rate_limit::{reset_from_headers, RateLimitedClient};
+use anyhow::{anyhow, bail, Result};
+use serde_derive::Deserialize;
+use std::env;
+use std::fs::{read_to_string, File};
+use std::io::copy;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A way of talking to GitHub.
+///
+/// Clients are shared between the threads fetching modules in parallel.
+pub trait Client: Sync {
+    /// Runs a GraphQL query, returning the raw JSON response.
+    fn graphql(&self, query: &str) -> Result<String, Error>;
+
+    /// Downloads the asset named `asset` of the release tagged `tag` into `dir`.
+    fn download_release_asset(
+        &self,
+        org: &str,
+        repo: &str,
+        tag: &str,
+        asset: &str,
+        dir: &Path,
+    ) -> Result<PathBuf, Error>;
+}
+
+pub const DEFAULT_HOST: &str = "github.com";
+
+/// Checks whether `output` is a GraphQL response carrying errors.
+fn is_graphql_response(output: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(output).is_ok_and(|response| {
+        response
+            .get("errors")
+            .is_some_and(|errors| errors.is_array())
+    })
+}
+
+/// Talks to GitHub by running the `gh` CLI.
+pub struct GhClient {
+    host: String,
+}
+
+impl Client for GhClient {
+    fn graphql(&self, query: &str) -> Result<String, Error> {
+        let query_parameter = format!("query={}", query);
+
+        let response = Command::new("gh")
+            .args(["api", "graphql", "--hostname", &self.host])
+            .args(["-f", &query_parameter])
+            .output()
+            .map_err(|err| Error::Network(format!("Could not run gh: {}", err)))?;
+
+        let stdout = String::from_utf8_lossy(&response.stdout).trim().to_string();
+        if response.status.code() == Some(0) {
+            Ok(stdout)
+        } else if is_graphql_response(&stdout) {
+            // `gh` fails on responses with GraphQL errors, but still prints them,
+            // along with any data that could be resolved.
+            Ok(stdout)
+        } else {
+            let stderr = response.stderr;
+            Err(Error::from_message(&String::from_utf8_lossy(&stderr)))
+        }
+    }
+
+    fn download_release_asset(
+        &self,
+        org: &str,
+        repo: &str,
+        tag: &str,
+        asset: &str,
+        dir: &Path,
+    ) -> Result<PathBuf, Error> {
+        let repo_parameter = format!("{}/{}/{}", self.host, org, repo);
+
+        let download = Command::new("gh")
+            .args(["release", "download", tag, "--repo", &repo_parameter])
+            .args(["--pattern", asset])
+            .arg("--dir")
+            .arg(dir)
+            .output()
+            .map_err(|err| Error::Network(format!("Could not run gh: {}", err)))?;
+
+        if download.status.code() == Some(0) {
+            Ok(dir.join(asset))
+        } else {
+            let stderr = download.stderr;
+            Err(Error::from_message(&String::from_utf8_lossy(&stderr)))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HttpReleaseAsset {
+    name: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct HttpRelease {
+    assets: Vec<HttpReleaseAsset>,
+}
+
+/// Talks to the GitHub GraphQL and REST APIs directly over HTTP.
+///
+/// The API is `GITHUB_API_URL`, or the API of the host: `https://api.github.com` for github.com,
+/// and `https://<host>/api/v3` for GitHub Enterprise Server.
+/// GraphQL queries go to `GITHUB_GRAPHQL_URL`, or the GraphQL API of the host.
+pub struct HttpClient {
+    api_url: String,
+    graphql_url: String,
+    token: String,
+    agent: ureq::Agent,
+}
+
+/// Finds a token for `host` in the environment, or the hosts file of the `gh` CLI.
+///
+/// Like `gh`, tokens for GitHub Enterprise Server hosts are read from `GH_ENTERPRISE_TOKEN`
+/// or `GITHUB_ENTERPRISE_TOKEN`, and tokens for github.com from `GH_TOKEN` or `GITHUB_TOKEN`.
+fn find_token(host: &str) -> Option<String> {
+    let variables = if host == DEFAULT_HOST {
+        ["GH_TOKEN", "GITHUB_TOKEN"]
+    } else {
+        ["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+    for variable in variables {
+        if let Ok(token) = env::var(variable) {
+            if !token.is_empty() {
+                return Some(token);
+            }
+        }
+    }
+
+    let gh_config_dir = match env::var("GH_CONFIG_DIR") {
+        Ok(gh_config_dir) => PathBuf::from(gh_config_dir),
+        Err(_) => match env::var("XDG_CONFIG_HOME") {
+            Ok(config_home) => PathBuf::from(config_home).join("gh"),
+            Err(_) => PathBuf::from(env::var("HOME").ok()?)
+                .join(".config")
+                .join("gh"),
+        },
+    };
+    let hosts_string = read_to_string(gh_config_dir.join("hosts.yml")).ok()?;
+    let hosts: serde_yaml::Value = serde_yaml::from_str(&hosts_string).ok()?;
+    hosts[host]["oauth_token"].as_str().map(str::to_string)
+}
+
+fn http_error(err: ureq::Error) -> Error {
+    match err {
+        ureq::Error::Status(code, response) => {
+            // Secondary rate limits are only told apart by their `Retry-After` header.
+            let rate_limited = response.header("x-ratelimit-remaining") == Some("0")
+                || response.header("retry-after").is_some();
+            let reset_at = reset_from_headers(
+                response.header("retry-after"),
+                response.header("x-ratelimit-reset"),
+            );
+            let body = response.into_string().unwrap_or_default();
+            let message = format!("HTTP {}: {}", code, body.trim());
+            match code {
+                401 => Error::Auth(message),
+                403 | 429 if rate_limited => Error::RateLimited(message, reset_at),
+                404 => Error::NotFound(message),
+                500.. => Error::Network(message),
+                _ => Error::from_message(&message),
+            }
+        }
+        ureq::Error::Transport(transport) => Error::Network(transport.to_string()),
+    }
+}
+
+impl HttpClient {
+    pub fn new(host: &str) -> Result<HttpClient> {
+        let (host_api_url, host_graphql_url) = if host == DEFAULT_HOST {
+            (
+                "https://api.github.com".to_string(),
+                "https://api.github.com/graphql".to_string(),
+            )
+        } else {
+            (
+                format!("https://{}/api/v3", host),
+                format!("https://{}/api/graphql", host),
+            )
+        };
+        let api_url = env::var("GITHUB_API_URL")
+            .unwrap_or(host_api_url)
+            .trim_end_matches('/')
+            .to_string();
+        let graphql_url = match env::var("GITHUB_GRAPHQL_URL") {
+            Ok(graphql_url) => graphql_url,
+            Err(_) if env::var("GITHUB_API_URL").is_ok() => format!("{}/graphql", api_url),
+            Err(_) => host_graphql_url,
+        };
+        let token = find_token(host).ok_or_else(|| {
+            anyhow!(
+                "No GitHub token found for {}. Set GH_TOKEN or GITHUB_TOKEN (GH_ENTERPRISE_TOKEN for GitHub Enterprise Server), or log in with `gh auth login --hostname {}`.",
+                host,
+                host
+            )
+        })?;
+        Ok(HttpClient {
+            api_url,
+            graphql_url,
+            token,
+            agent: ureq::agent(),
+        })
+    }
+
+    fn authorization(&self) -> String {
+        format!("bearer {}", self.token)
+    }
+}
+
+impl Client for HttpClient {
+    fn graphql(&self, query: &str) -> Result<String, Error> {
+        self.agent
+            .post(&self.graphql_url)
+            .set("Authorization", &self.authorization())
+            .send_json(ureq::json!({ "query": query }))
+            .map_err(http_error)?
+            .into_string()
+            .map_err(|err| Error::Network(err.to_string()))
+    }
+
+    fn download_release_asset(
+        &self,
+        org: &str,
+        repo: &str,
+        tag: &str,
+        asset: &str,
+        dir: &Path,
+    ) -> Result<PathBuf, Error> {
+        let release_url = format!(
+            "{}/repos/{}/{}/releases/tags/{}",
+            self.api_url, org, repo, tag
+        );
+        let release: HttpRelease = self
+            .agent
+            .get(&release_url)
+            .set("Authorization", &self.authorization())
+            .set("Accept", "application/vnd.github+json")
+            .call()
+            .map_err(http_error)?
+            .into_json()
+            .map_err(|err| Error::GraphQl(format!("Could not parse release: {}", err)))?;
+        let release_asset = release
+            .assets
+            .into_iter()
+            .find(|release_asset| release_asset.name == asset)
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "Release {} of {} has no {} asset",
+                    tag, repo, asset
+                ))
+            })?;
+
+        let response = self
+            .agent
+            .get(&release_asset.url)
+            .set("Authorization", &self.authorization())
+            .set("Accept", "application/octet-stream")
+            .call()
+            .map_err(http_error)?;
+        let asset_path = dir.join(asset);
+        copy(&mut response.into_reader(), &mut File::create(&asset_path)?)?;
+        Ok(asset_path)
+    }
+}
+
+/// Checks whether the `gh` CLI can be run.
+fn gh_is_installed() -> bool {
+    Command::new("gh")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Creates the client named `client`, `gh` or `http`, talking to `host`.
+///
+/// Without a name, `gh` is used when it is installed, and `http` otherwise.
+/// Without a host, github.com is used.
+/// Rate limited requests are retried up to `retries` times.
+pub fn new_client(
+    client: Option<&str>,
+    host: Option<&str>,
+    retries: u32,
+) -> Result<Box<dyn Client>> {
+    let host = host.unwrap_or(DEFAULT_HOST).to_string();
+    let client: Box<dyn Client> = match client {
+        Some("gh") => Box::new(GhClient { host }),
+        Some("http") => Box::new(HttpClient::new(&host)?),
+        Some(client) => bail!("Unknown client \"{}\". Use `gh` or `http`.", client),
+        None if gh_is_installed() => Box::new(GhClient { host }),
+        None => Box::new(HttpClient::new(&host)?),
+    };
+    Ok(Box::new(RateLimitedClient::new(client, retries)))
+}
@@ -1,25 +1,110 @@
+use crate::client::Client;
+use crate::error::Error;
 use crate::gh::get_logged_in_user;
+use crate::naming::{Discovery, Naming, DEFAULT_MODULE_TOPIC, DEFAULT_NAMING_PATTERN};
 use anyhow::Result;
 use merge::Merge;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{create_dir_all, metadata, read_to_string, write};
 use std::io::stdin;
 use std::path::PathBuf;
 
+/// A module declared in the `modules` section of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ManifestModule {
+    /// Organization to install the module from, instead of the configured one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+    /// Provider of the module, if it isn't prepended to the module.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Tag or version constraint to install. Defaults to the release with the highest semver tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Directory to install the module into, instead of `<modules-dir>/<provider>/<name>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// The `naming` section of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NamingConfig {
+    /// Regex matching module repositories, with `provider` and `name` captures.
+    /// Defaults to `^terraform-(?P<provider>[^-]+)-(?P<name>.*)-module$`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// How module repositories are discovered: by `name`, by `topic`, or `both`. Defaults to `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<Discovery>,
+    /// Topic of module repositories, when they are discovered by topic. Defaults to `terraform-module`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
 #[derive(Default, Serialize, Deserialize, Merge)]
 pub struct Config {
     pub org: Option<String>,
     pub provider: Option<String>,
+    pub modules_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance. Defaults to github.com.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub naming: Option<NamingConfig>,
+    /// Seconds to use cached responses for. `0` disables the cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
+    /// Times to retry requests that were rate limited by GitHub.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// How many modules to fetch from GitHub at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// Directory of templates overriding the built-in ones. Defaults to `.config/gh-tf-mod/templates`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modules: Option<BTreeMap<String, ManifestModule>>,
 }
 
+pub const DEFAULT_MODULES_DIR: &str = "modules";
+
 impl Config {
-    pub fn new(org: Option<String>, provider: Option<String>) -> Config {
-        let unwrapped_org = org.unwrap_or(get_logged_in_user());
+    pub fn new(
+        org: String,
+        provider: Option<String>,
+        modules_dir: Option<String>,
+        client: Option<String>,
+        host: Option<String>,
+    ) -> Config {
         Config {
-            org: Some(unwrapped_org),
-            provider: provider,
+            org: Some(org),
+            provider,
+            modules_dir,
+            client,
+            host,
+            ..Config::read()
         }
     }
+    /// The configured organization, defaulting to the logged in user.
+    pub fn org(&self, client: &dyn Client) -> Result<String, Error> {
+        match self.org {
+            Some(ref org) => Ok(org.clone()),
+            None => get_logged_in_user(client),
+        }
+    }
+    /// The configured naming convention of module repositories.
+    pub fn naming(&self) -> Result<Naming> {
+        let naming = self.naming.clone().unwrap_or_default();
+        Naming::new(
+            naming.pattern.as_deref().unwrap_or(DEFAULT_NAMING_PATTERN),
+            naming.discovery.unwrap_or(Discovery::Name),
+            naming.topic.as_deref().unwrap_or(DEFAULT_MODULE_TOPIC),
+        )
+    }
     pub fn save(&self) -> Result<()> {
         let config_path = PathBuf::from(".config");
         let config_file = &config_path.join("gh-tf-mod.yaml");
@@ -27,7 +112,7 @@ impl Config {
         if metadata(&config_path).is_err() {
             create_dir_all(&config_path)?;
         }
-        let confirmation = if metadata(&config_file).is_ok() {
+        let confirmation = if metadata(config_file).is_ok() {
             println!(
                 "File {} already exists. Please type \"yes\" to confirm replacement.",
                 &config_file.to_string_lossy()
@@ -50,34 +135,61 @@ impl Config {
         }
         Ok(())
     }
-    pub fn load(org: &Option<String>, provider: &Option<String>) -> Config {
+    /// Reads the config file as is, without applying any overrides or defaults.
+    fn read() -> Config {
         let config_path = PathBuf::from(".config");
         let config_file = &config_path.join("gh-tf-mod.yaml");
         let mut loaded_config = Config::default();
-        if metadata(&config_file).is_ok() {
+        if metadata(config_file).is_ok() {
             let config_string = read_to_string(config_file).expect("Could not read config");
             loaded_config =
                 serde_yaml::from_str(&config_string).expect("Could not deserialize config");
         }
+        loaded_config
+    }
+    pub fn load(
+        org: &Option<String>,
+        provider: &Option<String>,
+        modules_dir: &Option<String>,
+        host: &Option<String>,
+    ) -> Config {
+        let loaded_config = Config::read();
 
         let config_org = if org.is_some() {
             org.clone()
         } else {
-            if loaded_config.org.is_some() {
-                loaded_config.org.clone()
-            } else {
-                Some(get_logged_in_user())
-            }
+            loaded_config.org.clone()
         };
         let config_provider = if provider.is_some() {
             provider.clone()
         } else {
             loaded_config.provider.clone()
         };
+        let config_modules_dir = if modules_dir.is_some() {
+            modules_dir.clone()
+        } else if loaded_config.modules_dir.is_some() {
+            loaded_config.modules_dir.clone()
+        } else {
+            Some(DEFAULT_MODULES_DIR.to_string())
+        };
+        let config_host = if host.is_some() {
+            host.clone()
+        } else {
+            loaded_config.host.clone()
+        };
 
         Config {
             org: config_org,
             provider: config_provider,
+            modules_dir: config_modules_dir,
+            client: loaded_config.client,
+            host: config_host,
+            naming: loaded_config.naming,
+            cache_ttl: loaded_config.cache_ttl,
+            retries: loaded_config.retries,
+            concurrency: loaded_config.concurrency,
+            templates_dir: loaded_config.templates_dir,
+            modules: loaded_config.modules,
         }
     }
 }
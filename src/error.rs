@@ -0,0 +1,105 @@
+use std::fmt;
+use std::time::SystemTime;
+
+/// Why talking to GitHub failed.
+#[derive(Debug)]
+pub enum Error {
+    /// Not logged in, or the credentials were rejected.
+    Auth(String),
+    /// A repository, module, or release doesn't exist.
+    NotFound(String),
+    /// The GitHub API rate limit was exceeded, with when it resets, if GitHub said.
+    RateLimited(String, Option<SystemTime>),
+    /// A module or repository doesn't follow the naming convention.
+    NamingMismatch(String),
+    /// GitHub couldn't be reached, or `gh` couldn't be run.
+    Network(String),
+    /// GitHub answered with errors, or with a response that couldn't be understood.
+    GraphQl(String),
+    /// A local file couldn't be read or written.
+    Io(std::io::Error),
+}
+
+impl Error {
+    /// The exit code `main` exits with for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Io(_) => 1,
+            Error::Auth(_) => 3,
+            Error::NotFound(_) => 4,
+            Error::RateLimited(..) => 5,
+            Error::NamingMismatch(_) => 6,
+            Error::Network(_) => 7,
+            Error::GraphQl(_) => 8,
+        }
+    }
+
+    /// Classifies the error output of `gh` or the body of a failed GitHub API response.
+    pub fn from_message(message: &str) -> Error {
+        let lowercase_message = message.to_lowercase();
+        let message = message.trim().to_string();
+        if lowercase_message.contains("rate limit") {
+            Error::RateLimited(message, None)
+        } else if lowercase_message.contains("gh auth login")
+            || lowercase_message.contains("bad credentials")
+            || lowercase_message.contains("http 401")
+            || lowercase_message.contains("authentication")
+        {
+            Error::Auth(message)
+        } else if lowercase_message.contains("could not resolve to")
+            || lowercase_message.contains("http 404")
+            || lowercase_message.contains("not found")
+        {
+            Error::NotFound(message)
+        } else if lowercase_message.contains("error connecting")
+            || lowercase_message.contains("no such host")
+            || lowercase_message.contains("connection refused")
+            || lowercase_message.contains("timeout")
+        {
+            Error::Network(message)
+        } else {
+            Error::GraphQl(message)
+        }
+    }
+
+    /// An error for a response that couldn't be parsed as `what`.
+    pub fn parse(what: &str, err: serde_json::Error) -> Error {
+        Error::GraphQl(format!("Could not parse {}: {}", what, err))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Auth(message) => write!(
+                f,
+                "Not authenticated with GitHub: {}\nLog in with `gh auth login`, or set GH_TOKEN.",
+                message
+            ),
+            Error::NotFound(message) => write!(f, "{}", message),
+            Error::RateLimited(message, Some(reset_at)) => write!(
+                f,
+                "GitHub rate limit exceeded: {}\nWait for the rate limit to reset at {}, and try again.",
+                message,
+                httpdate::fmt_http_date(*reset_at)
+            ),
+            Error::RateLimited(message, None) => write!(
+                f,
+                "GitHub rate limit exceeded: {}\nWait for the rate limit to reset, and try again.",
+                message
+            ),
+            Error::NamingMismatch(message) => write!(f, "{}", message),
+            Error::Network(message) => write!(f, "Could not reach GitHub: {}", message),
+            Error::GraphQl(message) => write!(f, "GitHub returned an error: {}", message),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::Io(err)
+    }
+}
@@ -1,18 +1,122 @@
+use crate::client::Client;
+use crate::error::Error;
+use crate::install::split_module_provider;
+use crate::naming::{Discovery, Naming};
 use anyhow::Result;
-use regex::Regex;
+use serde::de::{Deserialize as _, DeserializeOwned, Deserializer};
 use serde_derive::{Deserialize, Serialize};
-use std::process::Command;
 
-pub fn get_logged_in_user() -> String {
-    let logged_in_user = Command::new("gh")
-        .args(&["api", "user", "--jq", ".login"])
-        .output()
-        .expect("Could get logged in user");
+/// An error in a GraphQL response, e.g. a repository that doesn't exist.
+///
+/// Errors can come alongside data, when only part of a query could be resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphQlError {
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<serde_json::Value>,
+    pub message: String,
+}
+
+/// A GraphQL response, with the errors that came alongside its data.
+pub trait GraphQlResponse {
+    fn errors(&self) -> &[GraphQlError];
+}
+
+#[derive(Deserialize)]
+struct GraphQlErrors {
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+/// Turns the errors of a response without usable data into an error.
+fn graphql_error(errors: &[GraphQlError]) -> Error {
+    let message = errors
+        .iter()
+        .map(|error| error.message.clone())
+        .collect::<Vec<_>>()
+        .join("\n");
+    match errors[0].error_type.as_deref() {
+        Some("NOT_FOUND") => Error::NotFound(message),
+        Some("RATE_LIMITED") => Error::RateLimited(message, None),
+        _ => Error::GraphQl(message),
+    }
+}
+
+/// Parses a GraphQL response as `what`.
+///
+/// When the data can't be parsed because of the errors in the response, the errors are returned instead.
+/// When the data can be parsed anyway, the errors are printed as warnings.
+fn parse_response<T: DeserializeOwned + GraphQlResponse>(
+    output: &str,
+    what: &str,
+) -> Result<T, Error> {
+    match serde_json::from_str::<T>(output) {
+        Ok(response) => {
+            for error in response.errors() {
+                eprintln!("Warning: {}", error.message);
+            }
+            Ok(response)
+        }
+        Err(err) => {
+            let errors = serde_json::from_str::<GraphQlErrors>(output)
+                .map(|graphql_errors| graphql_errors.errors)
+                .unwrap_or_default();
+            if errors.is_empty() {
+                Err(Error::parse(what, err))
+            } else {
+                Err(graphql_error(&errors))
+            }
+        }
+    }
+}
+
+/// Deserializes a list, skipping the items that are `null` because they couldn't be resolved.
+fn deserialize_resolved<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    let items = Vec::<Option<T>>::deserialize(deserializer)?;
+    Ok(items.into_iter().flatten().collect())
+}
+
+/// Replaces a "not found" error with a message naming the missing module.
+fn module_not_found(err: Error, repo: &str, org: &str) -> Error {
+    match err {
+        Error::NotFound(_) => Error::NotFound(format!("Module {} not found in {}", repo, org)),
+        err => err,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoggedInUserResponseViewer {
+    pub login: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoggedInUserResponseData {
+    pub viewer: LoggedInUserResponseViewer,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoggedInUserResponse {
+    pub data: LoggedInUserResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
 
-    String::from_utf8(logged_in_user.stdout)
-        .expect("Could not parse logged in user")
-        .trim()
-        .to_string()
+impl GraphQlResponse for LoggedInUserResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
+}
+
+pub fn get_logged_in_user(client: &dyn Client) -> Result<String, Error> {
+    let logged_in_user_output = client.graphql("query { viewer { login } }")?;
+    let logged_in_user_response: LoggedInUserResponse =
+        parse_response(&logged_in_user_output, "logged in user")?;
+    Ok(logged_in_user_response.data.viewer.login)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,6 +149,30 @@ pub struct ListModulesResponseRefs {
     pub nodes: Vec<ListModulesResponseRef>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListModulesResponseTopic {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListModulesResponseRepositoryTopic {
+    pub topic: ListModulesResponseTopic,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ListModulesResponseRepositoryTopics {
+    pub nodes: Vec<ListModulesResponseRepositoryTopic>,
+}
+
+impl ListModulesResponseRepositoryTopics {
+    pub fn names(&self) -> Vec<String> {
+        self.nodes
+            .iter()
+            .map(|node| node.topic.name.clone())
+            .collect()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListModulesResponseNode {
@@ -53,6 +181,8 @@ pub struct ListModulesResponseNode {
     pub provider: Option<String>,
     pub description: Option<String>,
     pub url: String,
+    #[serde(default)]
+    pub repository_topics: ListModulesResponseRepositoryTopics,
     pub releases: ListModulesResponseReleases,
     pub refs: ListModulesResponseRefs,
 }
@@ -63,6 +193,7 @@ pub struct ListModulesResponseSearch {
     pub page_info: ListModulesResponsePageInfo,
     pub repository_count: u64,
     pub filtered_repository_count: Option<u64>,
+    #[serde(deserialize_with = "deserialize_resolved")]
     pub nodes: Vec<ListModulesResponseNode>,
 }
 
@@ -74,30 +205,116 @@ pub struct ListModulesResponseData {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListModulesResponse {
     pub data: ListModulesResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for ListModulesResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
 }
 
 pub fn list_modules(
+    client: &dyn Client,
+    naming: &Naming,
     org: String,
     provider: Option<String>,
     first: Option<usize>,
     after: Option<String>,
-) -> Result<ListModulesResponse, std::io::Error> {
-    let query_provider = match provider {
-        Some(ref provider) => format!("{}-", provider),
-        None => "".to_string(),
-    };
-    let query_first = if first.is_some() {
-        format!("{}", first.unwrap())
-    } else {
-        "30".to_string()
+) -> Result<ListModulesResponse, Error> {
+    let search_query = list_modules_query(naming, &org, provider.as_deref());
+    search_module_repos(client, naming, &search_query, provider, false, first, after)
+}
+
+fn list_modules_query(naming: &Naming, org: &str, provider: Option<&str>) -> String {
+    format!("{} user:{}", naming.search_query(provider), org)
+}
+
+/// Filters that narrow down [`search_modules`], besides the text to search for.
+#[derive(Debug, Default)]
+pub struct SearchFilters {
+    /// Only modules of this provider.
+    pub provider: Option<String>,
+    /// Only modules pushed to on or after this date, e.g. `2024-01-31`.
+    pub updated_since: Option<String>,
+    /// Only archived modules, or only modules that aren't archived.
+    pub archived: Option<bool>,
+    /// Only modules with a release. GitHub can't search for these, so other modules are hidden from the results.
+    pub has_release: bool,
+}
+
+fn search_modules_query(naming: &Naming, org: &str, text: &str, filters: &SearchFilters) -> String {
+    let mut qualifiers = vec![
+        "in:name,description,topics,readme".to_string(),
+        naming.text_search_qualifiers(filters.provider.as_deref()),
+        format!("user:{}", org),
+    ];
+    if let Some(ref updated_since) = filters.updated_since {
+        qualifiers.push(format!("pushed:>={}", updated_since));
+    }
+    if let Some(archived) = filters.archived {
+        qualifiers.push(format!("archived:{}", archived));
+    }
+    format!(
+        "{} {}",
+        text.replace('\\', "\\\\").replace('"', "\\\""),
+        qualifiers
+            .into_iter()
+            .filter(|qualifier| !qualifier.is_empty())
+            .collect::<Vec<String>>()
+            .join(" ")
+    )
+}
+
+/// Searches the modules of `org` whose name, description, topics, or README match `text`.
+///
+/// Results are in the order GitHub ranks them in.
+pub fn search_modules(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    text: &str,
+    filters: SearchFilters,
+    first: Option<usize>,
+    after: Option<String>,
+) -> Result<ListModulesResponse, Error> {
+    let search_query = search_modules_query(naming, &org, text, &filters);
+    search_module_repos(
+        client,
+        naming,
+        &search_query,
+        filters.provider,
+        filters.has_release,
+        first,
+        after,
+    )
+}
+
+/// Runs a repository search, keeping only the repositories that are modules of `provider`, if one is given,
+/// and that have a release, if `has_release`.
+///
+/// The repositories left out are counted in `filteredRepositoryCount`.
+fn search_module_repos(
+    client: &dyn Client,
+    naming: &Naming,
+    search_query: &str,
+    provider: Option<String>,
+    has_release: bool,
+    first: Option<usize>,
+    after: Option<String>,
+) -> Result<ListModulesResponse, Error> {
+    let query_first = match first {
+        Some(first) => format!("{}", first),
+        None => "30".to_string(),
     };
-    let query_after = if after.is_some() {
-        format!("\"{}\"", after.unwrap())
-    } else {
-        "null".to_string()
+    let query_after = match after {
+        Some(after) => format!("\"{}\"", after),
+        None => "null".to_string(),
     };
-    let query = format!("query {{
-        search(query: \"terraform-{}module in:name user:{}\", type: REPOSITORY, first: {}, after: {}) {{
+    let query = format!(
+        "query {{
+        search(query: \"{}\", type: REPOSITORY, first: {}, after: {}) {{
             pageInfo {{
                 hasNextPage
                 endCursor
@@ -108,6 +325,13 @@ pub fn list_modules(
                     name
                     description
                     url
+                    repositoryTopics(first: 20) {{
+                        nodes {{
+                            topic {{
+                                name
+                            }}
+                        }}
+                    }}
                     releases(last: 1) {{
                         nodes {{
                             name
@@ -123,62 +347,303 @@ pub fn list_modules(
                 }}
             }}
         }}
-    }}", query_provider, org, query_first, query_after);
+    }}",
+        search_query, query_first, query_after
+    );
 
-    let query_parameter = format!("query={}", &query);
+    let listed_modules_output = client.graphql(&query)?;
 
-    let modules = Command::new("gh")
-        .args(&["api", "graphql", "-f", &query_parameter])
-        .output()
-        .expect("Could not list modules");
+    let mut list_modules_response: ListModulesResponse =
+        parse_response(&listed_modules_output, "modules")?;
+    let pre_sift_len = list_modules_response.data.search.nodes.len() as u64;
+    list_modules_response.data.search.nodes.retain(|item| {
+        naming.is_match(
+            &item.name,
+            &item.repository_topics.names(),
+            provider.as_deref(),
+        ) && (!has_release || !item.releases.nodes.is_empty())
+    });
+    let post_sift_len = list_modules_response.data.search.nodes.len() as u64;
+    list_modules_response.data.search.filtered_repository_count =
+        Some(pre_sift_len - post_sift_len);
+    for node in &mut list_modules_response.data.search.nodes {
+        if let Some((provider, short_name)) =
+            naming.classify(&node.name, &node.repository_topics.names())
+        {
+            node.provider = Some(provider);
+            node.short_name = Some(short_name);
+        }
+    }
+    Ok(list_modules_response)
+}
 
-    let stdout = modules.stdout;
+/// The page size used when following every page.
+const ALL_PAGES_FIRST: usize = 100;
 
-    let listed_modules_output = String::from_utf8(stdout)
-        .expect("Could not parse modules")
-        .trim()
-        .to_string();
+/// Parses a `--limit`, which must list at least one repo, module, release or tag.
+pub fn parse_limit(limit: &str) -> Result<usize, String> {
+    match limit.parse::<usize>() {
+        Ok(0) => Err("The limit must be at least 1".to_string()),
+        Ok(limit) => Ok(limit),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Iterates over the pages of [`list_modules`] or [`search_modules`], following `endCursor` until the last page,
+/// or until `limit` repos have been listed.
+pub struct ListModulesPages<'a> {
+    client: &'a dyn Client,
+    naming: &'a Naming,
+    search_query: String,
+    provider: Option<String>,
+    has_release: bool,
+    first: usize,
+    after: Option<String>,
+    remaining: Option<usize>,
+    /// Whether the limit only counts modules, rather than every repo including the hidden ones.
+    limit_modules: bool,
+    done: bool,
+}
+
+impl<'a> ListModulesPages<'a> {
+    pub fn new(
+        client: &'a dyn Client,
+        naming: &'a Naming,
+        org: String,
+        provider: Option<String>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> ListModulesPages<'a> {
+        ListModulesPages {
+            client,
+            naming,
+            search_query: list_modules_query(naming, &org, provider.as_deref()),
+            provider,
+            has_release: false,
+            first: first.unwrap_or(ALL_PAGES_FIRST),
+            after,
+            remaining: None,
+            limit_modules: false,
+            done: false,
+        }
+    }
+
+    /// Iterates over the pages of [`search_modules`] instead.
+    ///
+    /// Their limit only counts the modules found, after leaving out the ones `filters` hide.
+    pub fn search(
+        client: &'a dyn Client,
+        naming: &'a Naming,
+        org: String,
+        text: &str,
+        filters: SearchFilters,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> ListModulesPages<'a> {
+        ListModulesPages {
+            client,
+            naming,
+            search_query: search_modules_query(naming, &org, text, &filters),
+            provider: filters.provider,
+            has_release: filters.has_release,
+            first: first.unwrap_or(ALL_PAGES_FIRST),
+            after,
+            remaining: None,
+            limit_modules: true,
+            done: false,
+        }
+    }
 
-    if modules.status.code() == Some(0) {
-        let mut list_modules_response: ListModulesResponse =
-            serde_json::from_str(&listed_modules_output).expect("Could not parse modules");
-        let regex_provider = if provider.is_some() {
-            format!("{}-", &provider.unwrap())
-        } else {
-            "".to_string()
+    /// Stops after `limit` repos, including the ones hidden for not being modules, or after
+    /// `limit` modules when searching.
+    pub fn with_limit(mut self, limit: Option<usize>) -> ListModulesPages<'a> {
+        self.remaining = limit;
+        self
+    }
+
+    /// Merges every page into a single response, ending with the page info of the last page.
+    pub fn merge(self) -> Result<ListModulesResponse, Error> {
+        let mut merged: Option<ListModulesResponse> = None;
+        for page in self {
+            let page = page?;
+            merged = Some(match merged {
+                None => page,
+                Some(mut merged) => {
+                    merged.errors.extend(page.errors);
+                    let search = &mut merged.data.search;
+                    search.nodes.extend(page.data.search.nodes);
+                    search.filtered_repository_count = Some(
+                        search.filtered_repository_count.unwrap_or_default()
+                            + page
+                                .data
+                                .search
+                                .filtered_repository_count
+                                .unwrap_or_default(),
+                    );
+                    search.page_info = page.data.search.page_info;
+                    merged
+                }
+            });
+        }
+        merged.ok_or_else(|| Error::NotFound("No modules were listed".to_string()))
+    }
+}
+
+impl Iterator for ListModulesPages<'_> {
+    type Item = Result<ListModulesResponse, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.remaining == Some(0) {
+            return None;
+        }
+        let first = match self.remaining {
+            Some(remaining) => self.first.min(remaining),
+            None => self.first,
         };
-        let filter_regex_pattern = format!("terraform-{}.*-module", regex_provider);
-        let re = Regex::new(&filter_regex_pattern).unwrap();
-        let pre_sift_len = list_modules_response.data.search.nodes.len() as u64;
-        list_modules_response
-            .data
-            .search
-            .nodes
-            .retain(|item| re.is_match(item.name.as_str()));
-        let post_sift_len = list_modules_response.data.search.nodes.len() as u64;
-        list_modules_response.data.search.filtered_repository_count =
-            Some(pre_sift_len - post_sift_len);
-        for node in &mut list_modules_response.data.search.nodes {
-            let repo_name = node.name.clone();
-            let name_regex = Regex::new(r"^terraform-([^-]+)-(.*)-module$").unwrap();
-            let provider_capture = name_regex.captures(&repo_name).unwrap();
-            node.provider = Some(provider_capture.get(1).unwrap().as_str().to_string());
-            node.short_name = Some(provider_capture.get(2).unwrap().as_str().to_string());
+        let page = search_module_repos(
+            self.client,
+            self.naming,
+            &self.search_query,
+            self.provider.clone(),
+            self.has_release,
+            Some(first),
+            self.after.clone(),
+        );
+        match page {
+            Ok(ref page) => {
+                let search = &page.data.search;
+                let listed = if self.limit_modules {
+                    search.nodes.len()
+                } else {
+                    search.nodes.len() + search.filtered_repository_count.unwrap_or(0) as usize
+                };
+                self.remaining = self
+                    .remaining
+                    .map(|remaining| remaining.saturating_sub(listed));
+                self.after = search.page_info.end_cursor.clone();
+                self.done = !search.page_info.has_next_page || self.after.is_none();
+            }
+            Err(_) => self.done = true,
         }
-        Ok(list_modules_response)
-    } else {
-        let stderr = modules.stderr;
-        let listed_modules_stderr = String::from_utf8(stderr)
-            .expect("Could not parse modules")
-            .trim()
-            .to_string();
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            listed_modules_stderr,
-        ))
+        Some(page)
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindModuleRepoResponseNode {
+    pub name: String,
+    #[serde(default)]
+    pub repository_topics: ListModulesResponseRepositoryTopics,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FindModuleRepoResponseSearch {
+    #[serde(deserialize_with = "deserialize_resolved")]
+    pub nodes: Vec<FindModuleRepoResponseNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FindModuleRepoResponseData {
+    pub search: FindModuleRepoResponseSearch,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FindModuleRepoResponse {
+    pub data: FindModuleRepoResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for FindModuleRepoResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
+}
+
+/// The repository of a module, along with the provider and name of the module.
+#[derive(Debug, Clone)]
+pub struct ModuleRepo {
+    pub name: String,
+    pub provider: String,
+    pub short_name: String,
+}
+
+/// Finds the repository of a module like `aws-s3`, or `s3` when the provider is given.
+///
+/// Repositories discovered by name are derived from the naming pattern.
+/// Otherwise, the repositories of the org are searched for one classified as the module.
+pub fn find_module_repo(
+    client: &dyn Client,
+    naming: &Naming,
+    org: &str,
+    provider: Option<String>,
+    module: &str,
+) -> Result<ModuleRepo, Error> {
+    let (provider, short_name) = split_module_provider(provider, module)
+        .map_err(|err| Error::NamingMismatch(err.to_string()))?;
+    if naming.discovery() == Discovery::Name {
+        let name = naming.repo_name(&provider, &short_name);
+        if naming.parse(&name) != Some((provider.clone(), short_name.clone())) {
+            return Err(Error::NamingMismatch(format!(
+                "{} does not match the naming pattern \"{}\"",
+                name,
+                naming.pattern()
+            )));
+        }
+        return Ok(ModuleRepo {
+            name,
+            provider,
+            short_name,
+        });
+    }
+
+    let query = format!(
+        "query {{
+            search(query: \"{} user:{}\", type: REPOSITORY, first: 100) {{
+                nodes {{
+                    ... on Repository {{
+                        name
+                        repositoryTopics(first: 20) {{
+                            nodes {{
+                                topic {{
+                                    name
+                                }}
+                            }}
+                        }}
+                    }}
+                }}
+            }}
+        }}",
+        naming.module_search_query(&provider, &short_name),
+        org
+    );
+
+    let module_repo_output = client.graphql(&query)?;
+    let find_module_repo_response: FindModuleRepoResponse =
+        parse_response(&module_repo_output, "module repos")?;
+    find_module_repo_response
+        .data
+        .search
+        .nodes
+        .into_iter()
+        .find(|node| {
+            naming.classify(&node.name, &node.repository_topics.names())
+                == Some((provider.clone(), short_name.clone()))
+        })
+        .map(|node| ModuleRepo {
+            name: node.name,
+            provider: provider.clone(),
+            short_name: short_name.clone(),
+        })
+        .ok_or_else(|| {
+            Error::NotFound(format!(
+                "No repo found for module {}-{} in {}",
+                provider, short_name, org
+            ))
+        })
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListModuleResponseReleaseTag {
     pub name: String,
@@ -262,31 +727,51 @@ pub struct ListModuleResponseData {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListModuleResponse {
     pub data: ListModuleResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for ListModuleResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
 }
 
 pub fn list_module(
+    client: &dyn Client,
+    naming: &Naming,
     org: String,
     provider: Option<String>,
     module: String,
     first: Option<usize>,
     after: Option<String>,
-) -> Result<ListModuleResponse, std::io::Error> {
-    let query_provider = match provider {
-        Some(ref provider) => format!("{}-", provider),
-        None => "".to_string(),
-    };
+) -> Result<ListModuleResponse, Error> {
+    let module_repo = find_module_repo(client, naming, &org, provider, &module)?;
+    list_module_repo(client, &org, module_repo, first, after.clone(), after)
+}
 
-    let query_module = format!("terraform-{}{}-module", query_provider, module);
+/// Lists the releases and tags of a module repo, each after its own cursor.
+fn list_module_repo(
+    client: &dyn Client,
+    org: &str,
+    module_repo: ModuleRepo,
+    first: Option<usize>,
+    releases_after: Option<String>,
+    refs_after: Option<String>,
+) -> Result<ListModuleResponse, Error> {
+    let query_module = &module_repo.name;
 
-    let query_first = if first.is_some() {
-        format!("{}", first.unwrap())
-    } else {
-        "3".to_string()
+    let query_first = match first {
+        Some(first) => format!("{}", first),
+        None => "3".to_string(),
     };
-    let query_after = if after.is_some() {
-        format!("\"{}\"", after.unwrap())
-    } else {
-        "null".to_string()
+    let query_releases_after = match releases_after {
+        Some(after) => format!("\"{}\"", after),
+        None => "null".to_string(),
+    };
+    let query_refs_after = match refs_after {
+        Some(after) => format!("\"{}\"", after),
+        None => "null".to_string(),
     };
 
     let query = format!(
@@ -328,43 +813,508 @@ pub fn list_module(
                 }}
             }}
         }}",
-        query_module, org, query_first, query_after, query_first, query_after
+        query_module, org, query_first, query_releases_after, query_first, query_refs_after
+    );
+
+    let listed_module_output = client.graphql(&query)?;
+
+    let mut list_module_response: ListModuleResponse =
+        parse_response(&listed_module_output, "module")
+            .map_err(|err| module_not_found(err, query_module, org))?;
+    list_module_response.data.repository.provider = Some(module_repo.provider);
+    list_module_response.data.repository.short_name = Some(module_repo.short_name);
+    Ok(list_module_response)
+}
+
+/// Iterates over the pages of [`list_module`], following the `endCursor` of releases and tags
+/// until both run out, or until `limit` of each have been listed.
+pub struct ListModulePages<'a> {
+    client: &'a dyn Client,
+    org: String,
+    module_repo: ModuleRepo,
+    first: usize,
+    releases_after: Option<String>,
+    refs_after: Option<String>,
+    releases_done: bool,
+    refs_done: bool,
+    listed: usize,
+    limit: Option<usize>,
+}
+
+impl<'a> ListModulePages<'a> {
+    pub fn new(
+        client: &'a dyn Client,
+        naming: &Naming,
+        org: String,
+        provider: Option<String>,
+        module: String,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<ListModulePages<'a>, Error> {
+        let module_repo = find_module_repo(client, naming, &org, provider, &module)?;
+        Ok(ListModulePages {
+            client,
+            org,
+            module_repo,
+            first: first.unwrap_or(ALL_PAGES_FIRST),
+            releases_after: after.clone(),
+            refs_after: after,
+            releases_done: false,
+            refs_done: false,
+            listed: 0,
+            limit: None,
+        })
+    }
+
+    /// Stops after `limit` releases and `limit` tags.
+    pub fn with_limit(mut self, limit: Option<usize>) -> ListModulePages<'a> {
+        self.limit = limit;
+        self
+    }
+
+    /// Merges every page into a single response.
+    ///
+    /// Releases and tags end with the page info of the last page that listed any of them.
+    pub fn merge(self) -> Result<ListModuleResponse, Error> {
+        let mut merged: Option<ListModuleResponse> = None;
+        for page in self {
+            let page = page?;
+            merged = Some(match merged {
+                None => page,
+                Some(mut merged) => {
+                    merged.errors.extend(page.errors);
+                    let repository = &mut merged.data.repository;
+                    let page_repository = page.data.repository;
+                    if !page_repository.releases.edges.is_empty() {
+                        repository
+                            .releases
+                            .edges
+                            .extend(page_repository.releases.edges);
+                        repository.releases.page_info = page_repository.releases.page_info;
+                    }
+                    if !page_repository.refs.edges.is_empty() {
+                        repository.refs.edges.extend(page_repository.refs.edges);
+                        repository.refs.page_info = page_repository.refs.page_info;
+                    }
+                    merged
+                }
+            });
+        }
+        merged.ok_or_else(|| Error::NotFound("No releases or tags were listed".to_string()))
+    }
+}
+
+impl Iterator for ListModulePages<'_> {
+    type Item = Result<ListModuleResponse, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.limit.map(|limit| limit.saturating_sub(self.listed));
+        if (self.releases_done && self.refs_done) || remaining == Some(0) {
+            return None;
+        }
+        let first = match remaining {
+            Some(remaining) => self.first.min(remaining),
+            None => self.first,
+        };
+        let page = list_module_repo(
+            self.client,
+            &self.org,
+            self.module_repo.clone(),
+            Some(first),
+            self.releases_after.clone(),
+            self.refs_after.clone(),
+        );
+        match page {
+            Ok(ref page) => {
+                let repository = &page.data.repository;
+                let releases = &repository.releases;
+                let refs = &repository.refs;
+                self.listed += releases.edges.len().max(refs.edges.len());
+                if !self.releases_done {
+                    self.releases_after = releases.page_info.end_cursor.clone();
+                    self.releases_done =
+                        !releases.page_info.has_next_page || self.releases_after.is_none();
+                }
+                if !self.refs_done {
+                    self.refs_after = refs.page_info.end_cursor.clone();
+                    self.refs_done = !refs.page_info.has_next_page || self.refs_after.is_none();
+                }
+            }
+            Err(_) => {
+                self.releases_done = true;
+                self.refs_done = true;
+            }
+        }
+        Some(page)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReleaseResponseAsset {
+    pub name: String,
+    pub download_url: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetReleaseResponseAssets {
+    pub nodes: Vec<GetReleaseResponseAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetReleaseResponseTagCommit {
+    pub oid: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReleaseResponseRelease {
+    pub name: Option<String>,
+    pub tag_name: String,
+    pub url: String,
+    pub tag_commit: Option<GetReleaseResponseTagCommit>,
+    pub release_assets: GetReleaseResponseAssets,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetReleaseResponseRepository {
+    pub name: String,
+    pub short_name: Option<String>,
+    pub provider: Option<String>,
+    pub url: String,
+    pub release: Option<GetReleaseResponseRelease>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetReleaseResponseData {
+    pub repository: GetReleaseResponseRepository,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetReleaseResponse {
+    pub data: GetReleaseResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for GetReleaseResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
+}
+
+pub const RELEASE_ASSET_NAME: &str = "release.tar.gz";
+
+/// Gets the release of a module at `tag`, with its `release.tar.gz` asset.
+pub fn get_release(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    tag: &str,
+) -> Result<GetReleaseResponse, Error> {
+    let module_repo = find_module_repo(client, naming, &org, provider, &module)?;
+    let query_module = module_repo.name;
+
+    let query_release = format!("release(tagName: \"{}\")", tag);
+
+    let query = format!(
+        "{{
+            repository(name: \"{}\", owner: \"{}\") {{
+                name
+                url
+                release: {} {{
+                    name
+                    tagName
+                    url
+                    tagCommit {{
+                        oid
+                    }}
+                    releaseAssets(name: \"{}\", first: 1) {{
+                        nodes {{
+                            name
+                            downloadUrl
+                            size
+                        }}
+                    }}
+                }}
+            }}
+        }}",
+        query_module, org, query_release, RELEASE_ASSET_NAME
     );
 
-    let query_parameter = format!("query={}", &query);
-
-    let module = Command::new("gh")
-        .args(&["api", "graphql", "-f", &query_parameter])
-        .output()
-        .expect("Could not list module");
-
-    let stdout = module.stdout;
-
-    let listed_module_output = String::from_utf8(stdout)
-        .expect("Could not parse module")
-        .trim()
-        .to_string();
-
-    if module.status.code() == Some(0) {
-        let mut list_module_response: ListModuleResponse =
-            serde_json::from_str(&listed_module_output).expect("Could not parse module");
-        let repo_name = list_module_response.data.repository.name.clone();
-        let name_regex = Regex::new(r"^terraform-([^-]+)-(.*)-module$").unwrap();
-        let provider_capture = name_regex.captures(&repo_name).unwrap();
-        list_module_response.data.repository.provider =
-            Some(provider_capture.get(1).unwrap().as_str().to_string());
-        list_module_response.data.repository.short_name =
-            Some(provider_capture.get(2).unwrap().as_str().to_string());
-        Ok(list_module_response)
-    } else {
-        let stderr = module.stderr;
-        let listed_module_stderr = String::from_utf8(stderr)
-            .expect("Could not parse module")
-            .trim()
-            .to_string();
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            listed_module_stderr,
-        ))
+    let release_output = client.graphql(&query)?;
+
+    let mut get_release_response: GetReleaseResponse =
+        parse_response(&release_output, "release")
+            .map_err(|err| module_not_found(err, &query_module, &org))?;
+    let repo_name = get_release_response.data.repository.name.clone();
+    get_release_response.data.repository.provider = Some(module_repo.provider);
+    get_release_response.data.repository.short_name = Some(module_repo.short_name);
+    if get_release_response.data.repository.release.is_none() {
+        return Err(Error::NotFound(format!(
+            "Release {} not found for {}",
+            tag, repo_name
+        )));
+    }
+    Ok(get_release_response)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListTagsResponseRef {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTagsResponsePageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTagsResponseRefs {
+    pub nodes: Vec<ListTagsResponseRef>,
+    pub page_info: ListTagsResponsePageInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListTagsResponseRepository {
+    pub refs: ListTagsResponseRefs,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListTagsResponseData {
+    pub repository: ListTagsResponseRepository,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListTagsResponse {
+    pub data: ListTagsResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for ListTagsResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
+}
+
+/// Lists the names of every tag of a module, following pagination until the last page.
+pub fn list_module_tags(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+) -> Result<Vec<String>, Error> {
+    let module_repo = find_module_repo(client, naming, &org, provider, &module)?;
+    let query_module = module_repo.name;
+
+    let mut tags = vec![];
+    let mut after: Option<String> = None;
+    loop {
+        let query_after = match after {
+            Some(ref after) => format!("\"{}\"", after),
+            None => "null".to_string(),
+        };
+
+        let query = format!(
+            "{{
+                repository(name: \"{}\", owner: \"{}\") {{
+                    refs(refPrefix: \"refs/tags/\", first: 100, after: {}) {{
+                        nodes {{
+                            name
+                        }}
+                        pageInfo {{
+                            endCursor
+                            hasNextPage
+                        }}
+                    }}
+                }}
+            }}",
+            query_module, org, query_after
+        );
+
+        let module_tags_output = client.graphql(&query)?;
+        let list_tags_response: ListTagsResponse = parse_response(&module_tags_output, "tags")
+            .map_err(|err| module_not_found(err, &query_module, &org))?;
+        let refs = list_tags_response.data.repository.refs;
+        tags.extend(refs.nodes.into_iter().map(|tag| tag.name));
+
+        if !refs.page_info.has_next_page || refs.page_info.end_cursor.is_none() {
+            break;
+        }
+        after = refs.page_info.end_cursor;
+    }
+
+    Ok(tags)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleResponseReadme {
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleResponseRepository {
+    pub name: String,
+    pub description: Option<String>,
+    pub short_name: Option<String>,
+    pub provider: Option<String>,
+    pub url: String,
+    pub readme: Option<GetModuleResponseReadme>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleResponseData {
+    pub repository: GetModuleResponseRepository,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleResponse {
+    pub data: GetModuleResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for GetModuleResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
+}
+
+pub const README_FILE_NAME: &str = "README.md";
+
+/// Gets a module along with its README at `git_ref`, e.g. a tag or `HEAD`.
+pub fn get_module(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    git_ref: &str,
+) -> Result<GetModuleResponse, Error> {
+    let module_repo = find_module_repo(client, naming, &org, provider, &module)?;
+    let query_module = module_repo.name;
+
+    let query = format!(
+        "{{
+            repository(name: \"{}\", owner: \"{}\") {{
+                name
+                description
+                url
+                readme: object(expression: \"{}:{}\") {{
+                    ... on Blob {{
+                        text
+                    }}
+                }}
+            }}
+        }}",
+        query_module, org, git_ref, README_FILE_NAME
+    );
+
+    let module_output = client.graphql(&query)?;
+
+    let mut get_module_response: GetModuleResponse = parse_response(&module_output, "module")
+        .map_err(|err| module_not_found(err, &query_module, &org))?;
+    get_module_response.data.repository.provider = Some(module_repo.provider);
+    get_module_response.data.repository.short_name = Some(module_repo.short_name);
+    Ok(get_module_response)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleFilesResponseBlob {
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleFilesResponseEntry {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub object: Option<GetModuleFilesResponseBlob>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleFilesResponseTree {
+    pub entries: Vec<GetModuleFilesResponseEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleFilesResponseRepository {
+    pub name: String,
+    pub url: String,
+    pub files: Option<GetModuleFilesResponseTree>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleFilesResponseData {
+    pub repository: GetModuleFilesResponseRepository,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetModuleFilesResponse {
+    pub data: GetModuleFilesResponseData,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<GraphQlError>,
+}
+
+impl GraphQlResponse for GetModuleFilesResponse {
+    fn errors(&self) -> &[GraphQlError] {
+        &self.errors
+    }
+}
+
+/// Gets the files at the root of a module at `git_ref`, with the text of each.
+pub fn get_module_files(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    git_ref: &str,
+) -> Result<GetModuleFilesResponse, Error> {
+    let module_repo = find_module_repo(client, naming, &org, provider, &module)?;
+    let query_module = module_repo.name;
+
+    let query = format!(
+        "{{
+            repository(name: \"{}\", owner: \"{}\") {{
+                name
+                url
+                files: object(expression: \"{}:\") {{
+                    ... on Tree {{
+                        entries {{
+                            name
+                            type
+                            object {{
+                                ... on Blob {{
+                                    text
+                                }}
+                            }}
+                        }}
+                    }}
+                }}
+            }}
+        }}",
+        query_module, org, git_ref
+    );
+
+    let module_files_output = client.graphql(&query)?;
+
+    let get_module_files_response: GetModuleFilesResponse =
+        parse_response(&module_files_output, "module files")
+            .map_err(|err| module_not_found(err, &query_module, &org))?;
+    if get_module_files_response.data.repository.files.is_none() {
+        return Err(Error::NotFound(format!(
+            "{} not found in {}",
+            git_ref, query_module
+        )));
     }
+    Ok(get_module_files_response)
 }
@@ -0,0 +1,463 @@
+use crate::client::Client;
+use crate::gh::{get_release, list_module_tags, RELEASE_ASSET_NAME};
+use crate::lock::LockedModule;
+use crate::naming::Naming;
+use crate::version::{is_constraint, resolve, Constraints};
+use anyhow::{anyhow, bail, Context, Result};
+use flate2::read::GzDecoder;
+use hcl::expr::Expression;
+use hcl::Body;
+use sha2::{Digest, Sha256};
+use std::fs::{
+    canonicalize, create_dir_all, metadata, read_dir, read_to_string, remove_dir, remove_dir_all,
+    rename, File,
+};
+use std::io::copy;
+use std::path::{Path, PathBuf};
+use tar::Archive;
+
+/// Splits a module argument like `aws-s3@1.0.0` into the module and the optional version.
+pub fn parse_module_spec(spec: &str) -> (String, Option<String>) {
+    match spec.split_once('@') {
+        Some((module, version)) if !version.is_empty() => {
+            (module.to_string(), Some(version.to_string()))
+        }
+        Some((module, _)) => (module.to_string(), None),
+        None => (spec.to_string(), None),
+    }
+}
+
+fn warn_invalid_tags(module: &str, invalid_tags: &[String]) {
+    if !invalid_tags.is_empty() {
+        eprintln!(
+            "Ignoring tags of {} that are not valid semver: {}",
+            module,
+            invalid_tags.join(", ")
+        );
+    }
+}
+
+/// Resolves a version constraint like `~> 2.1` to the highest matching tag of a module.
+///
+/// Tags that are not valid semver are reported, and never picked.
+pub fn resolve_version(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    version: &str,
+    prerelease: bool,
+) -> Result<String> {
+    let constraints: Constraints = version.parse()?;
+    let tags = list_module_tags(client, naming, org, provider, module.clone())?;
+    let resolution = resolve(&constraints, &tags, prerelease);
+    warn_invalid_tags(&module, &resolution.invalid_tags);
+    match resolution.tag {
+        Some(tag) => Ok(tag),
+        None if prerelease => bail!("No tag of {} matches \"{}\"", module, version),
+        None => bail!(
+            "No tag of {} matches \"{}\". Use `--prerelease` to include prereleases.",
+            module,
+            version
+        ),
+    }
+}
+
+/// Resolves a module requested without a version to its latest version:
+/// the highest tag that is valid semver, and not a prerelease unless `prerelease`.
+///
+/// This is the version `outdated` compares installed modules against, so a module
+/// installed without a version is never reported as outdated right away.
+pub fn resolve_latest_version(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    prerelease: bool,
+) -> Result<String> {
+    let tags = list_module_tags(client, naming, org, provider, module.clone())?;
+    let resolution = resolve(&Constraints::any(), &tags, prerelease);
+    warn_invalid_tags(&module, &resolution.invalid_tags);
+    match resolution.tag {
+        Some(tag) => Ok(tag),
+        None if prerelease => bail!("No tag of {} is valid semver", module),
+        None => bail!(
+            "No tag of {} is a valid semver release. Use `--prerelease` to include prereleases.",
+            module
+        ),
+    }
+}
+
+/// Verifies that a downloaded release asset is the size GitHub reported,
+/// and that it is a gzipped tarball containing Terraform files.
+fn check_release_asset(asset_path: &Path, expected_size: u64) -> Result<()> {
+    let asset_size = metadata(asset_path)?.len();
+    if asset_size != expected_size {
+        bail!(
+            "Downloaded {} is {} bytes, expected {} bytes",
+            RELEASE_ASSET_NAME,
+            asset_size,
+            expected_size
+        );
+    }
+
+    let mut archive = Archive::new(GzDecoder::new(File::open(asset_path)?));
+    let mut has_terraform_files = false;
+    for entry in archive
+        .entries()
+        .with_context(|| format!("{} is not a valid tarball", RELEASE_ASSET_NAME))?
+    {
+        let entry =
+            entry.with_context(|| format!("{} is not a valid tarball", RELEASE_ASSET_NAME))?;
+        let entry_path = entry.path()?;
+        if entry_path.is_absolute()
+            || entry_path
+                .components()
+                .any(|component| component == std::path::Component::ParentDir)
+        {
+            bail!(
+                "{} contains an entry outside of the module directory: {}",
+                RELEASE_ASSET_NAME,
+                entry_path.to_string_lossy()
+            );
+        }
+        if entry_path.extension().is_some_and(|ext| ext == "tf") {
+            has_terraform_files = true;
+        }
+    }
+    if !has_terraform_files {
+        bail!("{} does not contain any .tf files", RELEASE_ASSET_NAME);
+    }
+    Ok(())
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The name the previously installed copy of a module is moved to while it is replaced.
+const PREVIOUS_DIR_NAME: &str = "previous";
+
+/// Unpacks a release asset into `module_path`, replacing anything already installed there.
+///
+/// The asset is unpacked next to the destination first, so a failed unpack
+/// never leaves a half-written module behind. The installed copy is then moved
+/// aside, and only removed once the new one is in place; if the new one can't be
+/// moved into place, the installed copy is put back.
+fn unpack_release_asset(asset_path: &Path, module_path: &Path) -> Result<()> {
+    let parent_path = module_path
+        .parent()
+        .ok_or_else(|| anyhow!("Invalid module path {}", module_path.to_string_lossy()))?;
+    create_dir_all(parent_path)?;
+
+    let staging_dir = tempfile::tempdir_in(parent_path)?;
+    let mut archive = Archive::new(GzDecoder::new(File::open(asset_path)?));
+    archive.unpack(staging_dir.path())?;
+
+    let previous_dir = match metadata(module_path) {
+        Ok(_) => {
+            let previous_dir = tempfile::tempdir_in(parent_path)?;
+            rename(module_path, previous_dir.path().join(PREVIOUS_DIR_NAME))?;
+            Some(previous_dir)
+        }
+        Err(_) => None,
+    };
+
+    // Once moved into place, the staging dir no longer exists, so dropping it removes nothing.
+    if let Err(err) = rename(staging_dir.path(), module_path) {
+        if let Some(previous_dir) = &previous_dir {
+            rename(previous_dir.path().join(PREVIOUS_DIR_NAME), module_path).with_context(
+                || {
+                    format!(
+                        "Could not restore {} after failing to replace it: {}",
+                        module_path.to_string_lossy(),
+                        err
+                    )
+                },
+            )?;
+        }
+        return Err(err.into());
+    }
+    if let Some(previous_dir) = previous_dir {
+        previous_dir.close().with_context(|| {
+            format!(
+                "Could not remove the previous copy of {}",
+                module_path.to_string_lossy()
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Where a module is installed.
+pub enum Destination<'a> {
+    /// Into `<modules-dir>/<provider>/<name>`.
+    ModulesDir(&'a Path),
+    /// Into exactly this path.
+    Path(&'a Path),
+}
+
+/// A module to install.
+pub struct ModuleRequest {
+    pub org: String,
+    pub provider: Option<String>,
+    pub module: String,
+    /// A tag, or a version constraint to resolve against the module's tags.
+    pub version: Option<String>,
+    /// Whether prereleases are considered when resolving a version constraint.
+    pub prerelease: bool,
+}
+
+impl ModuleRequest {
+    /// Requests the tag `tag` of an installed module.
+    pub fn for_locked_module(locked_module: &LockedModule, tag: String) -> ModuleRequest {
+        ModuleRequest {
+            org: locked_module.org.clone(),
+            provider: Some(locked_module.provider.clone()),
+            module: locked_module.name.clone(),
+            version: Some(tag),
+            prerelease: false,
+        }
+    }
+}
+
+/// Installs a release of a module, returning its lockfile entry.
+///
+/// When a `locked` entry is given, the release must still point at the locked
+/// commit and the asset must match the locked checksum, or nothing is installed.
+pub fn install_module(
+    client: &dyn Client,
+    naming: &Naming,
+    request: ModuleRequest,
+    destination: Destination,
+    locked: Option<&LockedModule>,
+) -> Result<LockedModule> {
+    let ModuleRequest {
+        org,
+        provider,
+        module,
+        version,
+        prerelease,
+    } = request;
+    let tag = match version {
+        Some(ref version) if is_constraint(version) => resolve_version(
+            client,
+            naming,
+            org.clone(),
+            provider.clone(),
+            module.clone(),
+            version,
+            prerelease,
+        )?,
+        Some(ref version) => version.clone(),
+        None => resolve_latest_version(
+            client,
+            naming,
+            org.clone(),
+            provider.clone(),
+            module.clone(),
+            prerelease,
+        )?,
+    };
+    let get_release_response = get_release(client, naming, org.clone(), provider, module, &tag)?;
+    let repository = get_release_response.data.repository;
+    let release = match repository.release {
+        Some(release) => release,
+        None => bail!("No release found for {}", repository.name),
+    };
+    let asset = match release.release_assets.nodes.into_iter().next() {
+        Some(asset) => asset,
+        None => bail!(
+            "Release {} of {} has no {} asset",
+            release.tag_name,
+            repository.name,
+            RELEASE_ASSET_NAME
+        ),
+    };
+
+    let download_dir = tempfile::tempdir()?;
+    let asset_path = client.download_release_asset(
+        &org,
+        &repository.name,
+        &release.tag_name,
+        &asset.name,
+        download_dir.path(),
+    )?;
+    check_release_asset(&asset_path, asset.size)?;
+    let sha256 = sha256_file(&asset_path)?;
+    let commit = release
+        .tag_commit
+        .map(|tag_commit| tag_commit.oid)
+        .unwrap_or_default();
+
+    if let Some(locked) = locked {
+        if commit != locked.commit {
+            bail!(
+                "Tag {} of {} points at commit {}, but {} is locked to commit {}",
+                release.tag_name,
+                repository.name,
+                commit,
+                locked.key(),
+                locked.commit
+            );
+        }
+        if sha256 != locked.sha256 {
+            bail!(
+                "Checksum of {} for {} {} changed from {} to {}",
+                RELEASE_ASSET_NAME,
+                repository.name,
+                release.tag_name,
+                locked.sha256,
+                sha256
+            );
+        }
+    }
+
+    let provider = repository.provider.unwrap_or_default();
+    let name = repository.short_name.unwrap_or_default();
+    let module_path = match destination {
+        Destination::ModulesDir(modules_dir) => modules_dir.join(&provider).join(&name),
+        Destination::Path(path) => path.to_path_buf(),
+    };
+    unpack_release_asset(&asset_path, &module_path)?;
+
+    Ok(LockedModule {
+        org,
+        repo: repository.name,
+        provider,
+        name,
+        version,
+        tag: release.tag_name,
+        asset_url: asset.download_url,
+        commit,
+        sha256,
+        path: module_path.to_string_lossy().to_string(),
+    })
+}
+
+/// Splits a module like `aws-s3` into its provider and name, unless the provider is given.
+pub fn split_module_provider(provider: Option<String>, module: &str) -> Result<(String, String)> {
+    match provider {
+        Some(provider) => Ok((provider, module.to_string())),
+        None => match module.split_once('-') {
+            Some((provider, name)) if !provider.is_empty() && !name.is_empty() => {
+                Ok((provider.to_string(), name.to_string()))
+            }
+            _ => bail!(
+                "Could not determine the provider of {}. Prepend it (e.g. `aws-{}`) or pass `-p|--provider`.",
+                module,
+                module
+            ),
+        },
+    }
+}
+
+/// Finds the `.tf` files under `dir`, skipping hidden directories like `.terraform` and `skip_dir`.
+fn find_terraform_files(
+    dir: &Path,
+    skip_dir: &Path,
+    terraform_files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for entry in read_dir(dir)? {
+        let entry_path = entry?.path();
+        let is_hidden = entry_path
+            .file_name()
+            .is_some_and(|file_name| file_name.to_string_lossy().starts_with('.'));
+        if is_hidden {
+            continue;
+        }
+        if entry_path.is_dir() {
+            if canonicalize(&entry_path)? != skip_dir {
+                find_terraform_files(&entry_path, skip_dir, terraform_files)?;
+            }
+        } else if entry_path.extension().is_some_and(|ext| ext == "tf") {
+            terraform_files.push(entry_path);
+        }
+    }
+    Ok(())
+}
+
+/// Lists the `module` blocks in the working tree whose `source` points at `module_path`.
+///
+/// Terraform files are parsed, so nested blocks and maps are looked through and comments are ignored.
+/// A file that can't be parsed fails the check, rather than hiding a reference.
+fn find_module_references(module_path: &Path) -> Result<Vec<String>> {
+    let module_path = canonicalize(module_path)?;
+
+    let mut terraform_files = vec![];
+    find_terraform_files(Path::new("."), &module_path, &mut terraform_files)?;
+
+    let mut references = vec![];
+    for terraform_file in terraform_files {
+        let terraform = read_to_string(&terraform_file)?;
+        let body: Body = hcl::parse(&terraform).with_context(|| {
+            format!(
+                "Could not parse {} to check it for references",
+                terraform_file.to_string_lossy()
+            )
+        })?;
+        let terraform_dir = terraform_file.parent().unwrap_or_else(|| Path::new("."));
+        for module_block in body.blocks().filter(|block| block.identifier() == "module") {
+            let source = module_block
+                .body()
+                .attributes()
+                .find(|attribute| attribute.key() == "source")
+                .map(|attribute| attribute.expr());
+            let source = match source {
+                Some(Expression::String(source)) => source,
+                _ => continue,
+            };
+            if !source.starts_with("./") && !source.starts_with("../") {
+                continue;
+            }
+            if let Ok(source_path) = canonicalize(terraform_dir.join(source)) {
+                if source_path == module_path {
+                    let name = module_block
+                        .labels()
+                        .first()
+                        .map(|label| label.as_str())
+                        .unwrap_or_default();
+                    references.push(format!(
+                        "module \"{}\" in {}",
+                        name,
+                        terraform_file.to_string_lossy()
+                    ));
+                }
+            }
+        }
+    }
+    Ok(references)
+}
+
+/// Removes the module installed at `module_path`, along with its parent directory if it is left empty.
+pub fn remove_module(module_path: &Path, force: bool) -> Result<()> {
+    if metadata(module_path).is_err() {
+        bail!(
+            "No module is installed in {}",
+            module_path.to_string_lossy()
+        );
+    }
+
+    if !force {
+        let references = find_module_references(module_path)?;
+        if !references.is_empty() {
+            bail!(
+                "{} is still used by:\n  {}\nRemove these references first, or use `--force` to remove it anyway.",
+                module_path.to_string_lossy(),
+                references.join("\n  ")
+            );
+        }
+    }
+
+    remove_dir_all(module_path)?;
+    if let Some(parent_path) = module_path.parent() {
+        if read_dir(parent_path)?.next().is_none() {
+            remove_dir(parent_path)?;
+        }
+    }
+    Ok(())
+}
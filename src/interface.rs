@@ -0,0 +1,274 @@
+use crate::client::Client;
+use crate::gh::{get_module_files, GetModuleFilesResponseEntry};
+use crate::naming::Naming;
+use crate::output::{Render, RenderOptions, TextTable};
+use crate::schema::DocumentKind;
+use crate::tables::{print_inputs_table, print_outputs_table};
+use anyhow::{Context, Result};
+use hcl::eval::{Context as EvalContext, Evaluate};
+use hcl::expr::Expression;
+use hcl::format::{Format, Formatter};
+use hcl::{Block, Body, Value};
+use schemars::JsonSchema;
+use serde_derive::Serialize;
+
+/// A `validation` block of a variable.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ModuleValidation {
+    pub condition: String,
+    pub error_message: Option<String>,
+}
+
+/// A `variable` block of a module.
+///
+/// Inputs without a default are required.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ModuleInput {
+    pub name: String,
+    /// The type constraint, as HCL, e.g. `list(string)`.
+    #[serde(rename = "type")]
+    pub input_type: Option<String>,
+    #[schemars(with = "Option<serde_json::Value>")]
+    pub default: Option<hcl::Value>,
+    pub required: bool,
+    pub description: Option<String>,
+    pub sensitive: bool,
+    pub validations: Vec<ModuleValidation>,
+}
+
+/// The columns inputs are listed under, in tables as well as in CSV and Markdown.
+pub const INPUT_COLUMNS: &[&str] = &[
+    "Input",
+    "Type",
+    "Default",
+    "Required",
+    "Description",
+    "Sensitive",
+    "Validation",
+];
+
+impl ModuleInput {
+    /// The values of the input, one for each of `INPUT_COLUMNS`.
+    ///
+    /// Validations are listed by their error message, or their condition when they have none.
+    pub fn cells(&self) -> Vec<String> {
+        let validation = self
+            .validations
+            .iter()
+            .map(|validation| {
+                validation
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| validation.condition.clone())
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        vec![
+            self.name.clone(),
+            self.input_type.clone().unwrap_or_default(),
+            self.default.as_ref().map(format_hcl).unwrap_or_default(),
+            self.required.to_string(),
+            self.description.clone().unwrap_or_default(),
+            self.sensitive.to_string(),
+            validation,
+        ]
+    }
+}
+
+/// An `output` block of a module.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ModuleOutput {
+    pub name: String,
+    pub description: Option<String>,
+    pub sensitive: bool,
+}
+
+/// The inputs and outputs of a module, in the order they are declared.
+#[derive(Debug, Default, Serialize)]
+pub struct ModuleInterface {
+    pub inputs: Vec<ModuleInput>,
+    pub outputs: Vec<ModuleOutput>,
+}
+
+/// The inputs and outputs of a module that were asked for.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ShownInterface {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<ModuleInput>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<ModuleOutput>>,
+}
+
+impl ModuleInterface {
+    /// Keeps only the inputs, the outputs, or both.
+    pub fn show(self, inputs: bool, outputs: bool) -> ShownInterface {
+        ShownInterface {
+            inputs: Some(self.inputs).filter(|_| inputs),
+            outputs: Some(self.outputs).filter(|_| outputs),
+        }
+    }
+}
+
+impl Render for ShownInterface {
+    const KIND: DocumentKind = DocumentKind::Interface;
+
+    fn print_tables(&self, options: &RenderOptions) {
+        if let Some(ref inputs) = self.inputs {
+            print_inputs_table(inputs, options.no_color);
+        }
+        if let Some(ref outputs) = self.outputs {
+            print_outputs_table(outputs, options.no_color);
+        }
+    }
+
+    fn text_tables(&self, _options: &RenderOptions) -> Vec<TextTable> {
+        let mut text_tables = vec![];
+        if let Some(ref inputs) = self.inputs {
+            text_tables.push(TextTable {
+                headers: INPUT_COLUMNS.to_vec(),
+                rows: inputs.iter().map(ModuleInput::cells).collect(),
+            });
+        }
+        if let Some(ref outputs) = self.outputs {
+            text_tables.push(TextTable {
+                headers: vec!["Output", "Description", "Sensitive"],
+                rows: outputs
+                    .iter()
+                    .map(|output| {
+                        vec![
+                            output.name.clone(),
+                            output.description.clone().unwrap_or_default(),
+                            output.sensitive.to_string(),
+                        ]
+                    })
+                    .collect(),
+            });
+        }
+        text_tables
+    }
+}
+
+/// Formats an expression or value as HCL on a single line, e.g. `list(string)`.
+pub fn format_hcl<T: Format>(hcl: &T) -> String {
+    let mut formatter = Formatter::builder().compact(true).build_vec();
+    hcl.format_string(&mut formatter).unwrap_or_default()
+}
+
+fn attribute<'a>(block: &'a Block, key: &str) -> Option<&'a Expression> {
+    block
+        .body()
+        .attributes()
+        .find(|attribute| attribute.key() == key)
+        .map(|attribute| attribute.expr())
+}
+
+fn string_attribute(block: &Block, key: &str) -> Option<String> {
+    let expression = attribute(block, key)?;
+    match expression {
+        Expression::String(string) => Some(string.clone()),
+        // Heredocs evaluate to their text, with the indentation of `<<-` heredocs stripped.
+        Expression::TemplateExpr(template) => match template.evaluate(&EvalContext::new()) {
+            Ok(Value::String(string)) => Some(string),
+            _ => Some(format_hcl(expression)),
+        },
+        _ => Some(format_hcl(expression)),
+    }
+}
+
+fn bool_attribute(block: &Block, key: &str) -> bool {
+    matches!(attribute(block, key), Some(Expression::Bool(true)))
+}
+
+fn block_name(block: &Block) -> Option<String> {
+    block
+        .labels()
+        .first()
+        .map(|label| label.as_str().to_string())
+}
+
+fn parse_input(block: &Block) -> Option<ModuleInput> {
+    let default = attribute(block, "default");
+    let validations = block
+        .body()
+        .blocks()
+        .filter(|validation| validation.identifier() == "validation")
+        .map(|validation| ModuleValidation {
+            condition: attribute(validation, "condition")
+                .map(format_hcl)
+                .unwrap_or_default(),
+            error_message: string_attribute(validation, "error_message"),
+        })
+        .collect();
+    Some(ModuleInput {
+        name: block_name(block)?,
+        input_type: attribute(block, "type").map(format_hcl),
+        default: default.cloned().map(hcl::Value::from),
+        required: default.is_none(),
+        description: string_attribute(block, "description"),
+        sensitive: bool_attribute(block, "sensitive"),
+        validations,
+    })
+}
+
+fn parse_output(block: &Block) -> Option<ModuleOutput> {
+    Some(ModuleOutput {
+        name: block_name(block)?,
+        description: string_attribute(block, "description"),
+        sensitive: bool_attribute(block, "sensitive"),
+    })
+}
+
+/// Adds the `variable` and `output` blocks of a Terraform file to the interface.
+pub fn parse_module_file(
+    interface: &mut ModuleInterface,
+    file_name: &str,
+    text: &str,
+) -> Result<()> {
+    let body: Body = hcl::parse(text).with_context(|| format!("Could not parse {}", file_name))?;
+    for block in body.blocks() {
+        match block.identifier() {
+            "variable" => interface.inputs.extend(parse_input(block)),
+            "output" => interface.outputs.extend(parse_output(block)),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses the inputs and outputs declared in the `.tf` files among the entries of a module's tree.
+pub fn parse_module_files(entries: &[GetModuleFilesResponseEntry]) -> Result<ModuleInterface> {
+    let mut interface = ModuleInterface::default();
+    for entry in entries {
+        if entry.entry_type != "blob" || !entry.name.ends_with(".tf") {
+            continue;
+        }
+        if let Some(text) = entry
+            .object
+            .as_ref()
+            .and_then(|object| object.text.as_ref())
+        {
+            parse_module_file(&mut interface, &entry.name, text)?;
+        }
+    }
+    Ok(interface)
+}
+
+/// Gets the inputs and outputs declared in the `.tf` files at the root of a module at `git_ref`.
+pub fn get_module_interface(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    git_ref: &str,
+) -> Result<ModuleInterface> {
+    let repository = get_module_files(client, naming, org, provider, module, git_ref)?
+        .data
+        .repository;
+    parse_module_files(
+        &repository
+            .files
+            .map(|files| files.entries)
+            .unwrap_or_default(),
+    )
+}
@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{metadata, read_to_string, write};
+
+pub const LOCKFILE: &str = ".gh-tf-mod.lock";
+
+/// The key of a module in the lockfile, e.g. `aws-s3`.
+pub fn module_key(provider: &str, name: &str) -> String {
+    format!("{}-{}", provider, name)
+}
+
+/// The exact release a module was installed from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedModule {
+    pub org: String,
+    pub repo: String,
+    pub provider: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub tag: String,
+    pub asset_url: String,
+    pub commit: String,
+    pub sha256: String,
+    pub path: String,
+}
+
+impl LockedModule {
+    pub fn key(&self) -> String {
+        module_key(&self.provider, &self.name)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub modules: BTreeMap<String, LockedModule>,
+}
+
+impl Lockfile {
+    pub fn load() -> Result<Lockfile> {
+        if metadata(LOCKFILE).is_err() {
+            return Ok(Lockfile::default());
+        }
+        let lockfile_string = read_to_string(LOCKFILE)?;
+        if lockfile_string.trim().is_empty() {
+            return Ok(Lockfile::default());
+        }
+        serde_yaml::from_str(&lockfile_string)
+            .with_context(|| format!("Could not deserialize {}", LOCKFILE))
+    }
+    pub fn save(&self) -> Result<()> {
+        let lockfile_string = serde_yaml::to_string(self)?;
+        write(LOCKFILE, lockfile_string)?;
+        Ok(())
+    }
+    pub fn insert(&mut self, locked_module: LockedModule) {
+        self.modules.insert(locked_module.key(), locked_module);
+    }
+}
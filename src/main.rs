@@ -1,14 +1,48 @@
 extern crate prettytable;
 
+mod cache;
+mod catalog;
+mod client;
 mod config;
+mod error;
 mod gh;
+mod install;
+mod interface;
+mod lock;
+mod model;
+mod naming;
+mod outdated;
+mod output;
+mod parallel;
+mod rate_limit;
+mod schema;
+mod search;
+mod serve;
+mod show;
+mod snippet;
+mod sync;
 mod tables;
+mod templates;
+mod update;
+mod version;
 
+use crate::cache::{Cache, CacheMode, DEFAULT_CACHE_TTL};
+use crate::client::{new_client, DEFAULT_HOST};
 use crate::config::Config;
-use anyhow::Result;
+use crate::error::Error;
+use crate::lock::Lockfile;
+use crate::model::{ModuleDetails, ModuleList};
+use crate::output::{print_output, OutputFormat, RenderOptions};
+use crate::parallel::DEFAULT_CONCURRENCY;
+use crate::rate_limit::DEFAULT_RETRIES;
+use crate::schema::DocumentKind;
+use crate::serve::{Registry, DEFAULT_ADDR};
+use crate::snippet::SourceStyle;
+use anyhow::{anyhow, bail, Result};
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
-use crate::tables::{print_module_table, print_modules_table};
+use crate::templates::{Templates, DEFAULT_TEMPLATES_DIR, SNIPPET_TEMPLATE};
 
 /// GitHub CLI extension for managing Terraform modules.
 #[derive(StructOpt, Debug)]
@@ -23,6 +57,17 @@ enum Commands {
         /// Provider to default to.
         #[structopt(short, long)]
         provider: Option<String>,
+        /// Directory to install modules into.
+        #[structopt(short, long)]
+        modules_dir: Option<String>,
+        /// How to talk to GitHub: `gh` to run the gh CLI, or `http` to call the GitHub API directly.
+        /// Defaults to `gh` when it is installed.
+        #[structopt(short, long)]
+        client: Option<String>,
+        /// GitHub host to default to, e.g. a GitHub Enterprise Server instance.
+        /// Defaults to github.com.
+        #[structopt(long)]
+        hostname: Option<String>,
     },
     /// List information about modules.
     #[structopt(
@@ -35,13 +80,24 @@ If a module name is provided, the information for that module is displayed.
 
 If more information is available for a paginated response, an `End Cursor` will be displayed.
 To display values after that cursor, provide `End Cursor` as the value of the `-a|--after` argument.
+Use `--all` to follow every page instead, and display the results together.
+Use `--limit` to stop after a number of repos, or a number of releases and tags of a module.
+
+Responses are cached for an hour under `~/.cache/gh-tf-mod` (or `$XDG_CACHE_HOME/gh-tf-mod`).
+Change how long with `cache_ttl` (in seconds) in the config file, or set it to `0` to disable the cache.
+Use `--refresh` to ignore cached responses, or `--offline` to only display cached responses.
 
-Only repositories that follow the naming pattern `^terraform-([^-]+)-(.*)-module$` (e.g. terraform-s3-lambda-module) will be displayed.
-Repos that do not match this pattern will be removed from results, and a `Hidden Repos` will be displayed to indicate how many were removed.
+Only repositories that follow the naming pattern will be displayed.
+The pattern defaults to `^terraform-(?P<provider>[^-]+)-(?P<name>.*)-module$` (e.g. terraform-s3-lambda-module), and can be changed in the `naming` section of the config file.
+When `discovery` is set to `topic` in the `naming` section, repositories with the `terraform-module` and `provider-<provider>` topics are displayed instead.
+Repos that are not modules will be removed from results, and a `Hidden Repos` will be displayed to indicate how many were removed.
 
 Change the paging size by changing the `-f|--first` argument.
 
 Minimal information is displayed by default. Use flags like `-l|--long` to display more information.
+
+Use `--output` to print `json`, `yaml`, `csv`, or `markdown` instead of tables, or `template=<file>` to render a Handlebars template.
+JSON and YAML follow a versioned schema, printed by the `schema` subcommand.
 "
     )]
     List {
@@ -60,9 +116,12 @@ Minimal information is displayed by default. Use flags like `-l|--long` to displ
         /// Show URLs.
         #[structopt(short, long)]
         url: bool,
-        /// Print output in JSON format.
-        #[structopt(short, long)]
+        /// Print output in JSON format. Short for `--output json`.
+        #[structopt(short, long, conflicts_with = "output")]
         json: bool,
+        /// Output format: `table`, `json`, `yaml`, `csv`, `markdown`, or `template=<file>`.
+        #[structopt(long)]
+        output: Option<OutputFormat>,
         /// Don't use color in output.
         #[structopt(long)]
         no_color: bool,
@@ -81,14 +140,439 @@ Minimal information is displayed by default. Use flags like `-l|--long` to displ
         /// Activate all optional display flags
         #[structopt(short, long)]
         long: bool,
+        /// List every page, instead of only the first one.
+        #[structopt(long)]
+        all: bool,
+        /// Stop listing pages after 'l' repos, or 'l' releases and tags of a module. Implies `--all`.
+        #[structopt(long, parse(try_from_str = gh::parse_limit))]
+        limit: Option<usize>,
+        /// Ignore cached responses, and cache the new ones.
+        #[structopt(long)]
+        refresh: bool,
+        /// Only display cached responses, without talking to GitHub.
+        #[structopt(long, conflicts_with = "refresh")]
+        offline: bool,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Search modules by name, description, topics, and README.
+    #[structopt(
+        name = "search",
+        long_about = "
+Search modules by name, description, topics, and README.
+
+The text is searched with GitHub search, so quote a phrase to match it exactly, e.g. `search '\"dead letter\" sqs'`.
+Only repositories that are modules, following the naming pattern or topics of the config file, are displayed.
+
+Results are ranked by where they match: name first, then topics, then description, then README.
+Results matching equally are kept in the order GitHub ranked them in.
+
+Narrow the search down with:
+  --provider:      modules of a provider
+  --has-release:   modules with a release
+  --updated-since: modules pushed to on or after a date, e.g. `2024-01-31`
+  --archived:      archived modules with `--archived=true`, or modules that aren't archived with `--archived=false`
+
+Modules without a release are hidden by `--has-release` before `--limit` is applied, so up to that many modules are displayed.
+
+Results are displayed like `ls`, and can be printed in another `--output` format.
+"
+    )]
+    Search {
+        /// Text to search for.
+        text: String,
+        /// Organization to search modules in.
+        #[structopt(short, long)]
+        org: Option<String>,
+        /// Only search modules of a provider.
+        #[structopt(short, long)]
+        provider: Option<String>,
+        /// Only show modules with a release.
+        #[structopt(long)]
+        has_release: bool,
+        /// Only show modules pushed to on or after a date. e.g. `2024-01-31`.
+        #[structopt(long, parse(try_from_str = search::parse_date))]
+        updated_since: Option<String>,
+        /// Only show archived modules, or only modules that aren't archived.
+        #[structopt(long)]
+        archived: Option<bool>,
+        /// Show descriptions.
+        #[structopt(short, long)]
+        description: bool,
+        /// Show URLs.
+        #[structopt(short, long)]
+        url: bool,
+        /// Print output in JSON format. Short for `--output json`.
+        #[structopt(short, long, conflicts_with = "output")]
+        json: bool,
+        /// Output format: `table`, `json`, `yaml`, `csv`, `markdown`, or `template=<file>`.
+        #[structopt(long)]
+        output: Option<OutputFormat>,
+        /// Don't use color in output.
+        #[structopt(long)]
+        no_color: bool,
+        /// First 'f' results to search. e.g. '-f 5' will search the first 5 results.
+        #[structopt(short, long)]
+        first: Option<usize>,
+        /// After 'a' cursor. e.g. '-a xyz=' will search results after the xyz= cursor.
+        #[structopt(short, long)]
+        after: Option<String>,
+        /// Activate all optional display flags
+        #[structopt(short, long)]
+        long: bool,
+        /// Search every page, instead of only the first one.
+        #[structopt(long)]
+        all: bool,
+        /// Stop searching pages after 'l' modules are found. Implies `--all`.
+        #[structopt(long, parse(try_from_str = gh::parse_limit))]
+        limit: Option<usize>,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Show a module with its README.
+    #[structopt(
+        name = "show",
+        long_about = "
+Show a module with its README.
+
+The README is shown at a release by appending `@<version>` (e.g. `aws-s3@1.0.0`).
+The version can also be a Terraform version constraint (e.g. `aws-s3@~> 2.1`), resolved like `install` does.
+If no version is provided, the README of the default branch is shown.
+
+The README is rendered for the terminal. Use `--raw` to print its Markdown as is.
+
+Use `--inputs` and `--outputs` to show the `variable` and `output` blocks of the `.tf` files at the root of the module instead.
+Inputs without a default are required.
+"
+    )]
+    Show {
+        /// Module to show. e.g. `aws-s3` or `aws-s3@1.0.0`.
+        module: String,
+        /// Organization to show the module from.
+        #[structopt(short, long)]
+        org: Option<String>,
+        /// Provider of the module.
+        /// If missing, it must be prepended. e.g. `aws-s3`.
+        #[structopt(short, long)]
+        provider: Option<String>,
+        /// Print the README as Markdown instead of rendering it.
+        #[structopt(long)]
+        raw: bool,
+        /// Show the inputs (variables) of the module instead of its README.
+        #[structopt(long)]
+        inputs: bool,
+        /// Show the outputs of the module instead of its README.
+        #[structopt(long)]
+        outputs: bool,
+        /// Print inputs and outputs in JSON format. Short for `--output json`.
+        #[structopt(short, long, conflicts_with = "output")]
+        json: bool,
+        /// Output format of inputs and outputs: `table`, `json`, `yaml`, `csv`, `markdown`, or `template=<file>`.
+        #[structopt(long)]
+        output: Option<OutputFormat>,
+        /// Don't use color in output.
+        #[structopt(long)]
+        no_color: bool,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
     },
+    /// Print a `module` block for a module.
+    #[structopt(
+        name = "snippet",
+        long_about = "
+Print a `module` block for a module, ready to paste into a Terraform configuration.
+
+The block is pinned to a release by appending `@<version>` (e.g. `aws-s3@1.0.0`), or to the highest release matching a version constraint (e.g. `aws-s3@~> 2.1`).
+If no version is provided, the block is pinned to the release with the highest semver tag (excluding prereleases).
+Every required input of the module at that release is included, with a placeholder value.
+
+The `--source` of the block can be:
+  https: git::https://<host>/<org>/<repo>.git?ref=<tag> (the default)
+  ssh:   git::ssh://git@<host>/<org>/<repo>.git?ref=<tag>
+  path:  the directory the module is installed into, e.g. ./modules/aws/s3
+
+The block is rendered from a Handlebars template.
+Override it with `snippet.hbs` in `.config/gh-tf-mod/templates` (or the `templates_dir` of the config file), or with `--template`.
+"
+    )]
+    Snippet {
+        /// Module to print a block for. e.g. `aws-s3` or `aws-s3@1.0.0`.
+        module: String,
+        /// Organization of the module.
+        #[structopt(short, long)]
+        org: Option<String>,
+        /// Provider of the module.
+        /// If missing, it must be prepended. e.g. `aws-s3`.
+        #[structopt(short, long)]
+        provider: Option<String>,
+        /// Directory modules are installed into, for `path` sources.
+        #[structopt(short, long)]
+        modules_dir: Option<String>,
+        /// Style of the module source: `https`, `ssh`, or `path`.
+        #[structopt(short, long, default_value = "https", possible_values = &["https", "ssh", "path"])]
+        source: SourceStyle,
+        /// Handlebars template to render the block with.
+        #[structopt(short, long)]
+        template: Option<PathBuf>,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Write a catalog of every module of an organization.
+    #[structopt(
+        name = "catalog",
+        long_about = "
+Write a catalog of every module of an organization, e.g. to publish as an internal registry.
+
+Every page of modules is listed, and each module is documented at its latest release (or its default branch, if it was never released):
+its description, its README, and the inputs and outputs declared in its `.tf` files.
+
+An index page and a page per module are written into `--out`, rendered from Handlebars templates.
+The built-in templates write Markdown. Override them with `catalog-index.hbs` and `catalog-module.hbs` in `.config/gh-tf-mod/templates` (or the `templates_dir` of the config file),
+and set `--extension` to match, e.g. `--extension html`.
+"
+    )]
+    Catalog {
+        /// Directory to write the catalog into.
+        #[structopt(long, default_value = "docs")]
+        out: PathBuf,
+        /// Organization to catalog the modules of.
+        #[structopt(short, long)]
+        org: Option<String>,
+        /// Only catalog the modules of a provider.
+        #[structopt(short, long)]
+        provider: Option<String>,
+        /// Extension of the pages.
+        #[structopt(short, long, default_value = "md")]
+        extension: String,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Serve the Terraform module registry protocol from GitHub releases.
+    #[structopt(
+        name = "serve",
+        long_about = "
+Serve the Terraform module registry protocol from GitHub releases, so Terraform can install modules with version constraints.
+
+The namespace of a module is the organization of its repository, e.g. `<host>/my-org/s3/aws` is `terraform-aws-s3-module` in `my-org`.
+The versions of a module are the tags of its releases, and each is downloaded from the `release.tar.gz` asset of its release.
+
+Terraform only talks to registries over HTTPS. Serve behind a proxy terminating TLS,
+or point a `host` block of the Terraform CLI configuration at `http://<addr>/v1/modules/`.
+"
+    )]
+    Serve {
+        /// Address to listen on.
+        #[structopt(long, default_value = DEFAULT_ADDR)]
+        addr: String,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Print the JSON Schema of JSON and YAML output.
+    #[structopt(
+        name = "schema",
+        long_about = "
+Print the JSON Schema of JSON and YAML output.
+
+Every document printed with `--json` or `--output json|yaml` has a `schema_version`, a `kind`, and `warnings`, besides its data.
+The schema version is bumped whenever a field is removed, renamed, or changes meaning. New fields can be added without bumping it.
+
+Provide a kind to print the schema of only those documents:
+  module_list: `ls`
+  module:      `ls <module>`
+  outdated:    `outdated`
+  interface:   `show --inputs/--outputs`
+"
+    )]
+    Schema {
+        /// Kind of document to print the schema of.
+        #[structopt(possible_values = &["module_list", "module", "outdated", "interface"])]
+        kind: Option<DocumentKind>,
+    },
+    /// Install a module from its release asset.
+    #[structopt(
+        name = "install",
+        long_about = "
+Install a module from its release asset.
+
+The module can be pinned to a release by appending `@<version>` (e.g. `aws-s3@1.0.0`).
+If no version is provided, the release with the highest semver tag (excluding prereleases) is installed.
+
+The version can also be a Terraform version constraint (e.g. `aws-s3@~> 2.1` or `aws-s3@>= 1.0, < 3.0`).
+Constraints are resolved against every tag of the module, and the highest matching version is installed.
+Prereleases are only considered when `--prerelease` is used, or when a constraint pins one exactly.
+Tags that are not valid semver are reported and never picked.
+
+The `release.tar.gz` asset of the release is downloaded, checked, and unpacked into `<modules-dir>/<provider>/<name>`.
+Any previously installed copy of the module is replaced.
+
+Installed versions are recorded in `.gh-tf-mod.lock`, along with the commit and checksum of the release asset.
+Use `--locked` to reinstall exactly the versions in `.gh-tf-mod.lock`.
+When `--locked` is used without a module, every module in the lockfile is reinstalled, and it fails if the lockfile records none.
+Installation fails if a locked tag has moved to another commit, or if the checksum of its asset has changed.
+
+When no module is provided, and `--locked` isn't used, the working tree is synced to the `modules` section of the config file.
+Declared modules that aren't installed with the declared version and path are installed.
+Installed modules that are no longer declared are removed.
+"
+    )]
+    Install {
+        /// Module to install. e.g. `aws-s3` or `aws-s3@1.0.0`.
+        module: Option<String>,
+        /// Organization to install the module from.
+        #[structopt(short, long)]
+        org: Option<String>,
+        /// Provider of the module.
+        /// If missing, it must be prepended. e.g. `aws-s3`.
+        #[structopt(short, long)]
+        provider: Option<String>,
+        /// Directory to install modules into.
+        #[structopt(short, long)]
+        modules_dir: Option<String>,
+        /// Install the versions recorded in the lockfile.
+        #[structopt(long)]
+        locked: bool,
+        /// Consider prereleases when resolving version constraints.
+        #[structopt(long)]
+        prerelease: bool,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Remove an installed module.
+    #[structopt(
+        name = "rm",
+        alias = "remove",
+        long_about = "
+Remove an installed module.
+
+The module is removed from `<modules-dir>/<provider>/<name>`.
+
+If any `.tf` file in the working tree has a `module` block with a `source` pointing at the module, nothing is removed.
+Use `-f|--force` to remove the module anyway.
+
+The module is also removed from `.gh-tf-mod.lock`. If its directory was already deleted, only its entry in the lockfile is removed.
+"
+    )]
+    Remove {
+        /// Module to remove. e.g. `aws-s3`.
+        module: String,
+        /// Provider of the module.
+        /// If missing, it must be prepended. e.g. `aws-s3`.
+        #[structopt(short, long)]
+        provider: Option<String>,
+        /// Directory modules are installed into.
+        #[structopt(short, long)]
+        modules_dir: Option<String>,
+        /// Remove the module even if it is still referenced.
+        #[structopt(short, long)]
+        force: bool,
+    },
+    /// List installed modules with newer versions available.
+    #[structopt(
+        name = "outdated",
+        long_about = "
+List installed modules with newer versions available.
+
+Every module in `.gh-tf-mod.lock` is compared against the tags of its repo:
+  `Current` is the installed version.
+  `Wanted` is the highest version matching the constraint the module was installed with.
+    Modules installed without a constraint want the highest version within their current major version.
+  `Latest` is the highest version that isn't a prerelease.
+
+Exits with status 9 when any module is older than its latest version.
+"
+    )]
+    Outdated {
+        /// Print output in JSON format. Short for `--output json`.
+        #[structopt(short, long, conflicts_with = "output")]
+        json: bool,
+        /// Output format: `table`, `json`, `yaml`, `csv`, `markdown`, or `template=<file>`.
+        #[structopt(long)]
+        output: Option<OutputFormat>,
+        /// Don't use color in output.
+        #[structopt(long)]
+        no_color: bool,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+    /// Update installed modules within their version constraints.
+    #[structopt(
+        name = "update",
+        long_about = "
+Update installed modules within their version constraints.
+
+If no modules are provided, every module in `.gh-tf-mod.lock` is updated.
+
+Each module is updated to the highest version matching the constraint it was installed with.
+Modules installed without a constraint are updated to the highest version within their current major version.
+Modules pinned to a tag are left alone, even with `--major`.
+
+Use `--major` to update modules to their latest version, even if it crosses a major version.
+The constraint of a module updated across a major version is replaced by `~> <major>.<minor>` of the new version.
+
+Updated modules replace the previously installed copy, and are recorded in `.gh-tf-mod.lock`.
+"
+    )]
+    Update {
+        /// Modules to update. e.g. `aws-s3`.
+        modules: Vec<String>,
+        /// Update to the latest version, even across major versions.
+        #[structopt(long)]
+        major: bool,
+        /// Show the updates that would be made without making them.
+        #[structopt(long)]
+        dry_run: bool,
+        /// GitHub host to talk to, e.g. a GitHub Enterprise Server instance.
+        #[structopt(long)]
+        hostname: Option<String>,
+    },
+}
+
+/// Runs the command, exiting with the code of the error it failed with.
+///
+/// Errors talking to GitHub exit with a code of their own (see `Error::exit_code`), and any other error with 1.
+fn main() {
+    match run() {
+        Ok(0) => {}
+        Ok(exit_code) => std::process::exit(exit_code),
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            let exit_code = err
+                .chain()
+                .find_map(|cause| cause.downcast_ref::<Error>())
+                .map_or(1, Error::exit_code);
+            std::process::exit(exit_code);
+        }
+    }
 }
 
-fn main() -> Result<()> {
+/// Runs the subcommand, returning the code to exit with when it succeeds.
+fn run() -> Result<i32> {
     let args = Commands::from_args();
     match args {
-        Commands::Config { org, provider } => {
-            let config = Config::new(org, provider);
+        Commands::Config {
+            org,
+            provider,
+            modules_dir,
+            client,
+            hostname,
+        } => {
+            let org = match org {
+                Some(org) => org,
+                None => gh::get_logged_in_user(&*new_client(
+                    client.as_deref(),
+                    hostname.as_deref(),
+                    DEFAULT_RETRIES,
+                )?)?,
+            };
+            let config = Config::new(org, provider, modules_dir, client, hostname);
             config.save()?;
         }
         Commands::List {
@@ -98,78 +582,564 @@ fn main() -> Result<()> {
             description,
             url,
             json,
+            output,
             no_color,
             first,
             after,
             tags,
             releases,
             long,
+            all,
+            limit,
+            refresh,
+            offline,
+            hostname,
         } => {
-            let config = Config::load(&org, &provider);
+            let config = Config::load(&org, &provider, &None, &hostname);
+            // Offline, every response comes from the cache, so no client, nor token, is needed.
+            let client = if offline {
+                None
+            } else {
+                Some(new_client(
+                    config.client.as_deref(),
+                    config.host.as_deref(),
+                    config.retries.unwrap_or(DEFAULT_RETRIES),
+                )?)
+            };
+            let client = || {
+                client
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("GitHub can't be queried with `--offline`"))
+            };
+            let naming = config.naming()?;
+            let all = all || limit.is_some();
+            let cache_mode = if offline {
+                CacheMode::Offline
+            } else if refresh {
+                CacheMode::Refresh
+            } else {
+                CacheMode::Normal
+            };
+            let cache = Cache::new(config.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL), cache_mode);
+            let host = config.host.as_deref().unwrap_or(DEFAULT_HOST);
+            let org = match config.org {
+                Some(ref org) => org.clone(),
+                None => cache.fetch(host, "", "viewer", || {
+                    Ok(gh::get_logged_in_user(client()?)?)
+                })?,
+            };
+            let query = format!(
+                "ls {:?} {:?} {:?} {:?} {} {:?} {}",
+                module,
+                provider,
+                first,
+                after,
+                all,
+                limit,
+                serde_json::to_string(&config.naming)?
+            );
+            let output_format = match output {
+                Some(output) => output,
+                None if json => OutputFormat::Json,
+                None => OutputFormat::Table,
+            };
+            let render_options = RenderOptions {
+                no_color,
+                description: description || long,
+                url: url || long,
+                tags: tags || long,
+                releases: releases || long,
+            };
             match module {
                 Some(module) => {
-                    let list_module_response = gh::list_module(
-                        config.org.unwrap().to_string(),
-                        provider,
-                        module,
-                        first,
-                        after,
-                    )?;
-
-                    if json {
-                        println!("{}", serde_json::to_string(&list_module_response)?);
-                    } else {
-                        if long {
-                            print_module_table(
-                                list_module_response,
-                                no_color,
-                                true,
-                                true,
-                                true,
-                                true,
-                            );
+                    let list_module_response = cache.fetch(host, &org, &query, || {
+                        if all {
+                            Ok(gh::ListModulePages::new(
+                                client()?,
+                                &naming,
+                                org.clone(),
+                                provider,
+                                module,
+                                first,
+                                after,
+                            )?
+                            .with_limit(limit)
+                            .merge()?)
                         } else {
-                            print_module_table(
-                                list_module_response,
-                                no_color,
-                                description,
-                                url,
-                                tags,
-                                releases,
-                            );
+                            Ok(gh::list_module(
+                                client()?,
+                                &naming,
+                                org.clone(),
+                                provider,
+                                module,
+                                first,
+                                after,
+                            )?)
                         }
-                    }
+                    })?;
+
+                    print_output(
+                        &ModuleDetails::from(list_module_response),
+                        &output_format,
+                        &render_options,
+                    )?;
                 }
                 None => {
-                    let list_modules_response =
-                        gh::list_modules(config.org.unwrap().to_string(), provider, first, after)
-                            .unwrap();
-                    if json {
-                        println!("{}", serde_json::to_string(&list_modules_response)?);
-                    } else {
-                        if long {
-                            print_modules_table(
-                                list_modules_response,
-                                no_color,
-                                true,
-                                true,
-                                true,
-                                true,
-                            );
+                    let list_modules_response = cache.fetch(host, &org, &query, || {
+                        if all {
+                            Ok(gh::ListModulesPages::new(
+                                client()?,
+                                &naming,
+                                org.clone(),
+                                provider,
+                                first,
+                                after,
+                            )
+                            .with_limit(limit)
+                            .merge()?)
                         } else {
-                            print_modules_table(
-                                list_modules_response,
-                                no_color,
-                                description,
-                                url,
-                                tags,
-                                releases,
-                            );
+                            Ok(gh::list_modules(
+                                client()?,
+                                &naming,
+                                org.clone(),
+                                provider,
+                                first,
+                                after,
+                            )?)
+                        }
+                    })?;
+                    print_output(
+                        &ModuleList::from(list_modules_response),
+                        &output_format,
+                        &render_options,
+                    )?;
+                }
+            }
+        }
+        Commands::Search {
+            text,
+            org,
+            provider,
+            has_release,
+            updated_since,
+            archived,
+            description,
+            url,
+            json,
+            output,
+            no_color,
+            first,
+            after,
+            long,
+            all,
+            limit,
+            hostname,
+        } => {
+            let config = Config::load(&org, &provider, &None, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let naming = config.naming()?;
+            let filters = gh::SearchFilters {
+                provider: config.provider.clone(),
+                updated_since,
+                archived,
+                has_release,
+            };
+            let mut search_modules_response = if all || limit.is_some() {
+                gh::ListModulesPages::search(
+                    &*client,
+                    &naming,
+                    config.org(&*client)?,
+                    &text,
+                    filters,
+                    first,
+                    after,
+                )
+                .with_limit(limit)
+                .merge()?
+            } else {
+                gh::search_modules(
+                    &*client,
+                    &naming,
+                    config.org(&*client)?,
+                    &text,
+                    filters,
+                    first,
+                    after,
+                )?
+            };
+            search::rank_modules(&mut search_modules_response, &text);
+            let output_format = match output {
+                Some(output) => output,
+                None if json => OutputFormat::Json,
+                None => OutputFormat::Table,
+            };
+            print_output(
+                &ModuleList::from(search_modules_response),
+                &output_format,
+                &RenderOptions {
+                    no_color,
+                    description: description || long,
+                    url: url || long,
+                    tags: long,
+                    releases: long,
+                },
+            )?;
+        }
+        Commands::Show {
+            module,
+            org,
+            provider,
+            raw,
+            inputs,
+            outputs,
+            json,
+            output,
+            no_color,
+            hostname,
+        } => {
+            let config = Config::load(&org, &provider, &None, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let naming = config.naming()?;
+            let org = config.org(&*client)?;
+            let (module, version) = install::parse_module_spec(&module);
+            let git_ref =
+                show::resolve_ref(&*client, &naming, &org, &config.provider, &module, version)?;
+            if inputs || outputs {
+                let interface = interface::get_module_interface(
+                    &*client,
+                    &naming,
+                    org,
+                    config.provider,
+                    module,
+                    &git_ref,
+                )?;
+                let output_format = match output {
+                    Some(output) => output,
+                    None if json => OutputFormat::Json,
+                    None => OutputFormat::Table,
+                };
+                print_output(
+                    &interface.show(inputs, outputs),
+                    &output_format,
+                    &RenderOptions {
+                        no_color,
+                        ..RenderOptions::default()
+                    },
+                )?;
+            } else if json || output.is_some() {
+                bail!("`--json` and `--output` can only be used with `--inputs` or `--outputs`");
+            } else {
+                let module_readme = show::get_module_readme(
+                    &*client,
+                    &naming,
+                    org,
+                    config.provider,
+                    module,
+                    git_ref,
+                )?;
+                show::print_module_readme(&module_readme, raw, no_color);
+            }
+        }
+        Commands::Snippet {
+            module,
+            org,
+            provider,
+            modules_dir,
+            source,
+            template,
+            hostname,
+        } => {
+            let config = Config::load(&org, &provider, &modules_dir, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let templates_dir = config
+                .templates_dir
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TEMPLATES_DIR.to_string());
+            let mut templates = Templates::load(Path::new(&templates_dir))?;
+            if let Some(template) = template {
+                templates.override_template(SNIPPET_TEMPLATE, &template)?;
+            }
+            let (module, version) = install::parse_module_spec(&module);
+            let snippet = snippet::build_snippet(
+                &*client,
+                &config.naming()?,
+                install::ModuleRequest {
+                    org: config.org(&*client)?,
+                    provider: config.provider.clone(),
+                    module,
+                    version,
+                    prerelease: false,
+                },
+                config.host.as_deref().unwrap_or(DEFAULT_HOST),
+                source,
+                Path::new(&config.modules_dir.clone().unwrap()),
+            )?;
+            print!("{}", templates.render(SNIPPET_TEMPLATE, &snippet)?);
+        }
+        Commands::Catalog {
+            out,
+            org,
+            provider,
+            extension,
+            hostname,
+        } => {
+            let config = Config::load(&org, &provider, &None, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let templates_dir = config
+                .templates_dir
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TEMPLATES_DIR.to_string());
+            let templates = Templates::load(Path::new(&templates_dir))?;
+            let catalog = catalog::build_catalog(
+                &*client,
+                &config.naming()?,
+                config.org(&*client)?,
+                config.provider.clone(),
+                &extension,
+                config.concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+            )?;
+            catalog::write_catalog(&catalog, &templates, &out, &extension)?;
+            println!(
+                "Wrote {} modules to {}",
+                catalog.modules.len(),
+                out.to_string_lossy()
+            );
+        }
+        Commands::Serve { addr, hostname } => {
+            let config = Config::load(&None, &None, &None, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let naming = config.naming()?;
+            let cache = Cache::new(
+                config.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL),
+                CacheMode::Normal,
+            );
+            let host = config.host.as_deref().unwrap_or(DEFAULT_HOST);
+            Registry::new(&*client, &naming, &cache, host).serve(&addr)?;
+        }
+        Commands::Schema { kind } => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schema::output_schema(kind))?
+            );
+        }
+        Commands::Install {
+            module,
+            org,
+            provider,
+            modules_dir,
+            locked,
+            prerelease,
+            hostname,
+        } => {
+            let config = Config::load(&org, &provider, &modules_dir, &hostname);
+            let mut lockfile = Lockfile::load()?;
+            if locked && lockfile.modules.is_empty() {
+                bail!("No modules are recorded in {}", lock::LOCKFILE);
+            }
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let naming = config.naming()?;
+            let modules_dir = PathBuf::from(config.modules_dir.clone().unwrap());
+            if locked {
+                let locked_modules = match module {
+                    Some(module) => {
+                        let (provider, name) =
+                            install::split_module_provider(config.provider, &module)?;
+                        let key = lock::module_key(&provider, &name);
+                        let locked_module = lockfile
+                            .modules
+                            .get(&key)
+                            .ok_or_else(|| anyhow!("{} is not in {}", key, lock::LOCKFILE))?;
+                        vec![locked_module.clone()]
+                    }
+                    None => lockfile.modules.values().cloned().collect(),
+                };
+                for locked_module in locked_modules {
+                    let installed_module = install::install_module(
+                        &*client,
+                        &naming,
+                        install::ModuleRequest::for_locked_module(
+                            &locked_module,
+                            locked_module.tag.clone(),
+                        ),
+                        install::Destination::Path(Path::new(&locked_module.path)),
+                        Some(&locked_module),
+                    )?;
+                    println!(
+                        "Installed {} {} to {}",
+                        installed_module.repo, installed_module.tag, installed_module.path
+                    );
+                }
+            } else if let Some(module) = module {
+                let (module, version) = install::parse_module_spec(&module);
+                let installed_module = install::install_module(
+                    &*client,
+                    &naming,
+                    install::ModuleRequest {
+                        org: config.org(&*client)?,
+                        provider: config.provider,
+                        module,
+                        version,
+                        prerelease,
+                    },
+                    install::Destination::ModulesDir(&modules_dir),
+                    None,
+                )?;
+                println!(
+                    "Installed {} {} to {}",
+                    installed_module.repo, installed_module.tag, installed_module.path
+                );
+                lockfile.insert(installed_module);
+                lockfile.save()?;
+            } else {
+                if config
+                    .modules
+                    .as_ref()
+                    .is_none_or(|modules| modules.is_empty())
+                {
+                    bail!("A module is required unless `--locked` is used, or modules are declared in the config file");
+                }
+                for action in sync::sync_manifest(&*client, &config, &mut lockfile, prerelease)? {
+                    match action {
+                        sync::SyncAction::Installed(installed_module) => println!(
+                            "Installed {} {} to {}",
+                            installed_module.repo, installed_module.tag, installed_module.path
+                        ),
+                        sync::SyncAction::UpToDate(locked_module) => println!(
+                            "{} {} is up to date in {}",
+                            locked_module.repo, locked_module.tag, locked_module.path
+                        ),
+                        sync::SyncAction::Removed(locked_module) => {
+                            println!("Removed {}", locked_module.path)
                         }
                     }
                 }
             }
         }
+        Commands::Remove {
+            module,
+            provider,
+            modules_dir,
+            force,
+        } => {
+            let config = Config::load(&None, &provider, &modules_dir, &None);
+            let (provider, name) = install::split_module_provider(config.provider, &module)?;
+            let key = lock::module_key(&provider, &name);
+            let mut lockfile = Lockfile::load()?;
+            let module_path = match lockfile.modules.get(&key) {
+                Some(locked_module) => PathBuf::from(&locked_module.path),
+                None => PathBuf::from(config.modules_dir.unwrap())
+                    .join(&provider)
+                    .join(&name),
+            };
+            // A module deleted by hand can still be removed from the lockfile.
+            if lockfile.modules.contains_key(&key) && !module_path.exists() {
+                eprintln!(
+                    "Warning: No module is installed in {}, removing {} from {} only",
+                    module_path.to_string_lossy(),
+                    key,
+                    lock::LOCKFILE
+                );
+            } else {
+                install::remove_module(&module_path, force)?;
+            }
+            if lockfile.modules.remove(&key).is_some() {
+                lockfile.save()?;
+            }
+            println!("Removed {}", module_path.to_string_lossy());
+        }
+        Commands::Outdated {
+            json,
+            output,
+            no_color,
+            hostname,
+        } => {
+            let config = Config::load(&None, &None, &None, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let lockfile = Lockfile::load()?;
+            let outdated_modules = outdated::check_outdated(
+                &*client,
+                &config.naming()?,
+                &lockfile,
+                config.concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+            )?;
+            let output_format = match output {
+                Some(output) => output,
+                None if json => OutputFormat::Json,
+                None => OutputFormat::Table,
+            };
+            print_output(
+                &outdated_modules,
+                &output_format,
+                &RenderOptions {
+                    no_color,
+                    ..RenderOptions::default()
+                },
+            )?;
+            if outdated_modules.any_outdated() {
+                return Ok(outdated::OUTDATED_EXIT_CODE);
+            }
+        }
+        Commands::Update {
+            modules,
+            major,
+            dry_run,
+            hostname,
+        } => {
+            let config = Config::load(&None, &None, &None, &hostname);
+            let client = new_client(
+                config.client.as_deref(),
+                config.host.as_deref(),
+                config.retries.unwrap_or(DEFAULT_RETRIES),
+            )?;
+            let mut lockfile = Lockfile::load()?;
+            let updates = update::update_modules(
+                &*client,
+                &config.naming()?,
+                &mut lockfile,
+                &modules,
+                major,
+                dry_run,
+                config.concurrency.unwrap_or(DEFAULT_CONCURRENCY),
+            )?;
+            let action = if dry_run { "Would update" } else { "Updated" };
+            for update in &updates {
+                println!(
+                    "{} {} from {} to {}",
+                    action, update.key, update.from, update.to
+                );
+            }
+            if updates.is_empty() {
+                println!("All modules are up to date");
+            }
+        }
     }
-    Ok(())
+    Ok(0)
 }
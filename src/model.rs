@@ -0,0 +1,323 @@
+use crate::gh::{GraphQlError, ListModuleResponse, ListModulesResponse};
+use crate::output::{Render, RenderOptions, TextTable};
+use crate::schema::DocumentKind;
+use crate::tables::{print_module_table, print_modules_table};
+use schemars::JsonSchema;
+use serde_derive::Serialize;
+
+/// A module, independent of the query it was listed with.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Module {
+    /// Name of the module, without its provider, e.g. `s3`.
+    pub name: String,
+    /// Provider of the module, e.g. `aws`.
+    pub provider: String,
+    /// Name of the repository of the module, e.g. `terraform-aws-s3-module`.
+    pub repo: String,
+    pub description: Option<String>,
+    /// URL of the repository of the module.
+    pub url: String,
+    /// Name of the newest tag.
+    pub latest_tag: Option<String>,
+    /// Name of the newest release.
+    pub latest_release: Option<String>,
+}
+
+/// A tag of a module, i.e. a version it can be installed at.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Tag {
+    pub name: String,
+    /// URL of the commit the tag points at.
+    pub commit_url: String,
+}
+
+/// A release of a module.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Release {
+    pub name: String,
+    /// Name of the tag of the release.
+    pub tag: String,
+    /// URL of the release page.
+    pub url: String,
+}
+
+/// How many items there are in total, and where to continue listing them.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Pagination {
+    /// Number of items in total.
+    pub total_count: u64,
+    /// Cursor to pass to `--after` to list the next page, if there is one.
+    pub next_cursor: Option<String>,
+}
+
+/// A page of items.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub pagination: Pagination,
+}
+
+/// A page of modules.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModuleList {
+    pub modules: Vec<Module>,
+    /// Number of repositories left out for not being modules, or, with `search --has-release`, for having no release.
+    pub hidden_count: u64,
+    pub pagination: Pagination,
+    /// Errors GitHub answered alongside the modules.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// A module, with pages of its tags and releases, newest first.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModuleDetails {
+    pub module: Module,
+    pub tags: Page<Tag>,
+    pub releases: Page<Release>,
+    /// Errors GitHub answered alongside the module.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// The cursor to list the next page after, if there is one.
+fn pagination(total_count: u64, has_next_page: bool, end_cursor: Option<String>) -> Pagination {
+    Pagination {
+        total_count,
+        next_cursor: end_cursor.filter(|_| has_next_page),
+    }
+}
+
+fn warnings(errors: &[GraphQlError]) -> Vec<String> {
+    errors.iter().map(|error| error.message.clone()).collect()
+}
+
+impl From<ListModulesResponse> for ModuleList {
+    fn from(list_modules_response: ListModulesResponse) -> ModuleList {
+        let warnings = warnings(&list_modules_response.errors);
+        let search = list_modules_response.data.search;
+        let modules = search
+            .nodes
+            .into_iter()
+            .map(|node| Module {
+                name: match node.short_name {
+                    Some(short_name) => short_name,
+                    None => node.name.clone(),
+                },
+                provider: node.provider.unwrap_or_default(),
+                repo: node.name,
+                description: node.description,
+                url: node.url,
+                latest_tag: node.refs.nodes.into_iter().next().map(|tag| tag.name),
+                latest_release: node
+                    .releases
+                    .nodes
+                    .into_iter()
+                    .next()
+                    .map(|release| release.name),
+            })
+            .collect();
+        ModuleList {
+            modules,
+            hidden_count: search.filtered_repository_count.unwrap_or(0),
+            pagination: pagination(
+                search.repository_count,
+                search.page_info.has_next_page,
+                search.page_info.end_cursor,
+            ),
+            warnings,
+        }
+    }
+}
+
+impl From<ListModuleResponse> for ModuleDetails {
+    fn from(list_module_response: ListModuleResponse) -> ModuleDetails {
+        let warnings = warnings(&list_module_response.errors);
+        let repository = list_module_response.data.repository;
+        let tags: Vec<Tag> = repository
+            .refs
+            .edges
+            .into_iter()
+            .map(|tag| Tag {
+                name: tag.node.name,
+                commit_url: tag.node.target.commit_url,
+            })
+            .collect();
+        let releases: Vec<Release> = repository
+            .releases
+            .edges
+            .into_iter()
+            .map(|release| Release {
+                name: release.node.name,
+                tag: release.node.tag.name,
+                url: release.node.url,
+            })
+            .collect();
+        ModuleDetails {
+            module: Module {
+                name: match repository.short_name {
+                    Some(short_name) => short_name,
+                    None => repository.name.clone(),
+                },
+                provider: repository.provider.unwrap_or_default(),
+                repo: repository.name,
+                description: repository.description,
+                url: repository.url,
+                latest_tag: tags.first().map(|tag| tag.name.clone()),
+                latest_release: releases.first().map(|release| release.name.clone()),
+            },
+            tags: Page {
+                items: tags,
+                pagination: pagination(
+                    repository.refs.total_count,
+                    repository.refs.page_info.has_next_page,
+                    repository.refs.page_info.end_cursor,
+                ),
+            },
+            releases: Page {
+                items: releases,
+                pagination: pagination(
+                    repository.releases.total_count,
+                    repository.releases.page_info.has_next_page,
+                    repository.releases.page_info.end_cursor,
+                ),
+            },
+            warnings,
+        }
+    }
+}
+
+fn module_headers(options: &RenderOptions) -> Vec<&'static str> {
+    let mut headers = vec!["Name", "Provider"];
+    if options.description {
+        headers.push("Description");
+    }
+    if options.url {
+        headers.push("URL");
+    }
+    if options.tags {
+        headers.push("Latest Tag");
+    }
+    if options.releases {
+        headers.push("Latest Release");
+    }
+    headers
+}
+
+fn module_row(module: &Module, options: &RenderOptions) -> Vec<String> {
+    let mut row = vec![module.name.clone(), module.provider.clone()];
+    if options.description {
+        row.push(module.description.clone().unwrap_or_default());
+    }
+    if options.url {
+        row.push(module.url.clone());
+    }
+    if options.tags {
+        row.push(module.latest_tag.clone().unwrap_or_default());
+    }
+    if options.releases {
+        row.push(module.latest_release.clone().unwrap_or_default());
+    }
+    row
+}
+
+impl Render for ModuleList {
+    const KIND: DocumentKind = DocumentKind::ModuleList;
+
+    fn warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
+
+    fn print_tables(&self, options: &RenderOptions) {
+        print_modules_table(
+            self,
+            options.no_color,
+            options.description,
+            options.url,
+            options.tags,
+            options.releases,
+        );
+    }
+
+    fn text_tables(&self, options: &RenderOptions) -> Vec<TextTable> {
+        vec![TextTable {
+            headers: module_headers(options),
+            rows: self
+                .modules
+                .iter()
+                .map(|module| module_row(module, options))
+                .collect(),
+        }]
+    }
+}
+
+impl Render for ModuleDetails {
+    const KIND: DocumentKind = DocumentKind::Module;
+
+    fn warnings(&self) -> Vec<String> {
+        self.warnings.clone()
+    }
+
+    fn print_tables(&self, options: &RenderOptions) {
+        print_module_table(
+            self,
+            options.no_color,
+            options.description,
+            options.url,
+            options.tags,
+            options.releases,
+        );
+    }
+
+    fn text_tables(&self, options: &RenderOptions) -> Vec<TextTable> {
+        let mut text_tables = vec![TextTable {
+            headers: module_headers(options),
+            rows: vec![module_row(&self.module, options)],
+        }];
+        if options.tags {
+            let mut headers = vec!["Tag"];
+            if options.url {
+                headers.push("URL");
+            }
+            let rows = self
+                .tags
+                .items
+                .iter()
+                .map(|tag| {
+                    let mut row = vec![tag.name.clone()];
+                    if options.url {
+                        row.push(tag.commit_url.clone());
+                    }
+                    row
+                })
+                .collect();
+            text_tables.push(TextTable { headers, rows });
+        }
+        if options.releases {
+            let mut headers = vec!["Release"];
+            if options.tags {
+                headers.push("Tag");
+            }
+            if options.url {
+                headers.push("URL");
+            }
+            let rows = self
+                .releases
+                .items
+                .iter()
+                .map(|release| {
+                    let mut row = vec![release.name.clone()];
+                    if options.tags {
+                        row.push(release.tag.clone());
+                    }
+                    if options.url {
+                        row.push(release.url.clone());
+                    }
+                    row
+                })
+                .collect();
+            text_tables.push(TextTable { headers, rows });
+        }
+        text_tables
+    }
+}
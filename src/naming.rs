@@ -0,0 +1,266 @@
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+
+/// The default naming convention, e.g. `terraform-aws-s3-module`.
+pub const DEFAULT_NAMING_PATTERN: &str = r"^terraform-(?P<provider>[^-]+)-(?P<name>.*)-module$";
+
+/// The default topic of module repositories.
+pub const DEFAULT_MODULE_TOPIC: &str = "terraform-module";
+
+/// The prefix of the topic naming the provider of a module repository, e.g. `provider-aws`.
+pub const PROVIDER_TOPIC_PREFIX: &str = "provider-";
+
+/// How module repositories are discovered.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Discovery {
+    /// Repositories whose name matches the naming pattern.
+    Name,
+    /// Repositories with the module topic, and a `provider-<provider>` topic.
+    Topic,
+    /// Repositories found either way.
+    Both,
+}
+
+/// A piece of a repository name.
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Provider,
+    Name,
+}
+
+/// How module repositories are named and discovered.
+///
+/// The pattern is a regex with `provider` and `name` captures, surrounded by literal text,
+/// e.g. `^tfmod-(?P<provider>[^-]+)-(?P<name>.+)$`.
+///
+/// Repositories discovered by topic are modules of the provider named by their `provider-<provider>` topic.
+/// Their module name is the repository name, without a leading `<provider>-`.
+#[derive(Debug, Clone)]
+pub struct Naming {
+    pattern: String,
+    regex: Regex,
+    parts: Vec<Part>,
+    discovery: Discovery,
+    topic: String,
+}
+
+/// Finds the end of the group starting at `start`, returning the index of its closing parenthesis.
+fn find_group_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut index = start;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 1,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Splits a naming pattern into the literal text around its `provider` and `name` captures.
+fn parse_parts(pattern: &str) -> Result<Vec<Part>> {
+    let unanchored = pattern.strip_prefix('^').unwrap_or(pattern);
+    let unanchored = unanchored.strip_suffix('$').unwrap_or(unanchored);
+    let chars: Vec<char> = unanchored.chars().collect();
+    let unsupported = || {
+        anyhow!(
+            "Naming pattern \"{}\" must be literal text around the `provider` and `name` captures",
+            pattern
+        )
+    };
+
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => match chars.get(index + 1) {
+                Some(escaped) if !escaped.is_alphanumeric() => {
+                    literal.push(*escaped);
+                    index += 1;
+                }
+                _ => return Err(unsupported()),
+            },
+            '(' => {
+                let end = find_group_end(&chars, index).ok_or_else(unsupported)?;
+                let group: String = chars[index..end].iter().collect();
+                let part =
+                    if group.starts_with("(?P<provider>") || group.starts_with("(?<provider>") {
+                        Part::Provider
+                    } else if group.starts_with("(?P<name>") || group.starts_with("(?<name>") {
+                        Part::Name
+                    } else {
+                        return Err(unsupported());
+                    };
+                if !literal.is_empty() {
+                    parts.push(Part::Literal(literal.clone()));
+                    literal.clear();
+                }
+                parts.push(part);
+                index = end;
+            }
+            '.' | '[' | ']' | '{' | '}' | '*' | '+' | '?' | '|' | ')' | '^' | '$' => {
+                return Err(unsupported())
+            }
+            character => literal.push(character),
+        }
+        index += 1;
+    }
+    if !literal.is_empty() {
+        parts.push(Part::Literal(literal));
+    }
+
+    if !parts.contains(&Part::Provider) || !parts.contains(&Part::Name) {
+        bail!(
+            "Naming pattern \"{}\" must have `provider` and `name` captures, e.g. `(?P<provider>[^-]+)`",
+            pattern
+        );
+    }
+    Ok(parts)
+}
+
+impl Naming {
+    pub fn new(pattern: &str, discovery: Discovery, topic: &str) -> Result<Naming> {
+        let regex = Regex::new(pattern)
+            .map_err(|err| anyhow!("Invalid naming pattern \"{}\": {}", pattern, err))?;
+        let parts = parse_parts(pattern)?;
+        Ok(Naming {
+            pattern: pattern.to_string(),
+            regex,
+            parts,
+            discovery,
+            topic: topic.to_string(),
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn discovery(&self) -> Discovery {
+        self.discovery
+    }
+
+    /// Splits a repository name into the provider and name of its module.
+    pub fn parse(&self, repo: &str) -> Option<(String, String)> {
+        let captures = self.regex.captures(repo)?;
+        Some((
+            captures.name("provider")?.as_str().to_string(),
+            captures.name("name")?.as_str().to_string(),
+        ))
+    }
+
+    /// Splits a repository into the provider and name of its module, using its topics.
+    fn parse_topics(&self, repo: &str, topics: &[String]) -> Option<(String, String)> {
+        if !topics.contains(&self.topic) {
+            return None;
+        }
+        let provider = topics
+            .iter()
+            .find_map(|topic| topic.strip_prefix(PROVIDER_TOPIC_PREFIX))?;
+        let name = repo.strip_prefix(&format!("{}-", provider)).unwrap_or(repo);
+        Some((provider.to_string(), name.to_string()))
+    }
+
+    /// Classifies a repository as a module, returning its provider and name.
+    ///
+    /// With [`Discovery::Both`], the naming pattern takes precedence over topics.
+    pub fn classify(&self, repo: &str, topics: &[String]) -> Option<(String, String)> {
+        match self.discovery {
+            Discovery::Name => self.parse(repo),
+            Discovery::Topic => self.parse_topics(repo, topics),
+            Discovery::Both => self.parse(repo).or_else(|| self.parse_topics(repo, topics)),
+        }
+    }
+
+    /// Checks whether a repository is a module, and belongs to `provider` if one is given.
+    pub fn is_match(&self, repo: &str, topics: &[String], provider: Option<&str>) -> bool {
+        match self.classify(repo, topics) {
+            Some((repo_provider, _)) => provider.is_none_or(|provider| provider == repo_provider),
+            None => false,
+        }
+    }
+
+    /// The repository name of a module following the naming pattern, e.g. `terraform-aws-s3-module`.
+    pub fn repo_name(&self, provider: &str, name: &str) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(literal) => literal.as_str(),
+                Part::Provider => provider,
+                Part::Name => name,
+            })
+            .collect()
+    }
+
+    /// The search terms matching the repository names of modules, e.g. `terraform-aws-module`.
+    ///
+    /// The words of the literal text are kept, along with the provider if one is given.
+    fn search_terms(&self, provider: Option<&str>) -> String {
+        let mut words = vec![];
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => words.extend(
+                    literal
+                        .split(|character: char| !character.is_alphanumeric())
+                        .filter(|word| !word.is_empty()),
+                ),
+                Part::Provider => words.extend(provider),
+                Part::Name => {}
+            }
+        }
+        words.join("-")
+    }
+
+    /// The repository search qualifiers matching modules, e.g. `terraform-aws-module in:name`.
+    ///
+    /// With [`Discovery::Both`], every repository is searched, and the results are classified afterwards.
+    pub fn search_query(&self, provider: Option<&str>) -> String {
+        match self.discovery {
+            Discovery::Name => format!("{} in:name", self.search_terms(provider)),
+            Discovery::Topic => match provider {
+                Some(provider) => format!(
+                    "topic:{} topic:{}{}",
+                    self.topic, PROVIDER_TOPIC_PREFIX, provider
+                ),
+                None => format!("topic:{}", self.topic),
+            },
+            Discovery::Both => "".to_string(),
+        }
+    }
+
+    /// The repository search qualifiers narrowing a free-text search down to modules.
+    ///
+    /// Unlike [`Naming::search_query`], the search terms of the naming pattern are left out,
+    /// since `in:name` would keep the text from matching anything but names.
+    /// Repositories found by a search must still be checked with [`Naming::is_match`].
+    pub fn text_search_qualifiers(&self, provider: Option<&str>) -> String {
+        match self.discovery {
+            Discovery::Topic => self.search_query(provider),
+            _ => "".to_string(),
+        }
+    }
+
+    /// The repository search qualifiers matching the repository of the module `name`,
+    /// when it can't be derived from the naming pattern.
+    pub fn module_search_query(&self, provider: &str, name: &str) -> String {
+        match self.discovery {
+            Discovery::Topic => format!(
+                "{} in:name topic:{} topic:{}{}",
+                name, self.topic, PROVIDER_TOPIC_PREFIX, provider
+            ),
+            _ => format!("{} in:name", name),
+        }
+    }
+}
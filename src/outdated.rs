@@ -0,0 +1,140 @@
+use crate::client::Client;
+use crate::gh::list_module_tags;
+use crate::lock::{LockedModule, Lockfile};
+use crate::naming::Naming;
+use crate::output::{Render, RenderOptions, TextTable};
+use crate::parallel::map_concurrently;
+use crate::schema::DocumentKind;
+use crate::tables::print_outdated_table;
+use crate::version::{is_constraint, parse_tag, resolve, Constraints};
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde_derive::Serialize;
+
+/// The exit code `outdated` exits with when any module is older than its latest version,
+/// distinct from the exit codes of failures.
+pub const OUTDATED_EXIT_CODE: i32 = 9;
+
+/// How an installed module compares to the versions available for it.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OutdatedModule {
+    pub name: String,
+    pub provider: String,
+    pub repo: String,
+    pub constraint: Option<String>,
+    pub current: String,
+    pub wanted: Option<String>,
+    pub latest: Option<String>,
+    pub outdated: bool,
+}
+
+/// Checks whether `tag` is older than `other_tag`, falling back to a name comparison for non-semver tags.
+pub fn is_older(tag: &str, other_tag: &str) -> bool {
+    match (parse_tag(tag), parse_tag(other_tag)) {
+        (Some(version), Some(other_version)) => version < other_version,
+        _ => tag != other_tag,
+    }
+}
+
+/// Compares an installed module against the tags available for it.
+///
+/// Modules installed without a constraint want the highest version within their current major version.
+pub fn check_module(
+    client: &dyn Client,
+    naming: &Naming,
+    locked_module: &LockedModule,
+) -> Result<OutdatedModule> {
+    let tags = list_module_tags(
+        client,
+        naming,
+        locked_module.org.clone(),
+        Some(locked_module.provider.clone()),
+        locked_module.name.clone(),
+    )?;
+
+    let latest = resolve(&Constraints::any(), &tags, false).tag;
+    let wanted = match locked_module.version {
+        Some(ref version) if is_constraint(version) => resolve(&version.parse()?, &tags, false).tag,
+        Some(_) => Some(locked_module.tag.clone()),
+        None => match parse_tag(&locked_module.tag) {
+            Some(current) => {
+                let same_major = format!("~> {}.{}", current.major, current.minor);
+                resolve(&same_major.parse()?, &tags, false).tag
+            }
+            None => latest.clone(),
+        },
+    };
+    let outdated = latest
+        .as_ref()
+        .is_some_and(|latest| is_older(&locked_module.tag, latest));
+
+    Ok(OutdatedModule {
+        name: locked_module.name.clone(),
+        provider: locked_module.provider.clone(),
+        repo: locked_module.repo.clone(),
+        constraint: locked_module.version.clone(),
+        current: locked_module.tag.clone(),
+        wanted,
+        latest,
+        outdated,
+    })
+}
+
+/// Every installed module, compared to the versions available for it.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OutdatedModules {
+    pub modules: Vec<OutdatedModule>,
+}
+
+impl OutdatedModules {
+    /// Whether any module is older than its latest version.
+    pub fn any_outdated(&self) -> bool {
+        self.modules
+            .iter()
+            .any(|outdated_module| outdated_module.outdated)
+    }
+}
+
+/// Compares every module in the lockfile against the tags available for it,
+/// checking up to `concurrency` modules at once.
+pub fn check_outdated(
+    client: &dyn Client,
+    naming: &Naming,
+    lockfile: &Lockfile,
+    concurrency: usize,
+) -> Result<OutdatedModules> {
+    let locked_modules: Vec<&LockedModule> = lockfile.modules.values().collect();
+    let modules = map_concurrently(&locked_modules, concurrency, |locked_module| {
+        check_module(client, naming, locked_module)
+    })
+    .into_iter()
+    .collect::<Result<_>>()?;
+    Ok(OutdatedModules { modules })
+}
+
+impl Render for OutdatedModules {
+    const KIND: DocumentKind = DocumentKind::Outdated;
+
+    fn print_tables(&self, options: &RenderOptions) {
+        print_outdated_table(&self.modules, options.no_color);
+    }
+
+    fn text_tables(&self, _options: &RenderOptions) -> Vec<TextTable> {
+        vec![TextTable {
+            headers: vec!["Name", "Provider", "Current", "Wanted", "Latest"],
+            rows: self
+                .modules
+                .iter()
+                .map(|outdated_module| {
+                    vec![
+                        outdated_module.name.clone(),
+                        outdated_module.provider.clone(),
+                        outdated_module.current.clone(),
+                        outdated_module.wanted.clone().unwrap_or_default(),
+                        outdated_module.latest.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        }]
+    }
+}
@@ -0,0 +1,134 @@
+use crate::schema::{Document, DocumentKind};
+use crate::templates::Templates;
+use anyhow::{bail, Result};
+use serde::ser;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The name `--output template=<file>` templates are registered as.
+const OUTPUT_TEMPLATE: &str = "output";
+
+/// How the output of a command is printed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputFormat {
+    /// Tables for the terminal.
+    Table,
+    Json,
+    Yaml,
+    /// One CSV table after another, separated by empty lines.
+    Csv,
+    /// One Markdown table after another, separated by empty lines.
+    Markdown,
+    /// Rendered with the Handlebars template in the file.
+    Template(PathBuf),
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(output_format: &str) -> Result<OutputFormat> {
+        match output_format {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "csv" => Ok(OutputFormat::Csv),
+            "markdown" => Ok(OutputFormat::Markdown),
+            _ => match output_format.strip_prefix("template=") {
+                Some(template_path) if !template_path.is_empty() => {
+                    Ok(OutputFormat::Template(PathBuf::from(template_path)))
+                }
+                _ => bail!(
+                    "Unknown output format {}. Use `table`, `json`, `yaml`, `csv`, `markdown`, or `template=<file>`.",
+                    output_format
+                ),
+            },
+        }
+    }
+}
+
+/// How output is displayed in the formats that are tables.
+#[derive(Debug, Default)]
+pub struct RenderOptions {
+    pub no_color: bool,
+    pub description: bool,
+    pub url: bool,
+    pub tags: bool,
+    pub releases: bool,
+}
+
+/// A table of plain text, for the output formats that aren't for the terminal.
+pub struct TextTable {
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Output that can be printed in every output format.
+pub trait Render: ser::Serialize {
+    /// What the output is called in JSON and YAML documents.
+    const KIND: DocumentKind;
+
+    /// Errors GitHub answered alongside the output.
+    fn warnings(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Prints the output as tables for the terminal.
+    fn print_tables(&self, options: &RenderOptions);
+
+    /// The output as plain text tables, for CSV and Markdown.
+    fn text_tables(&self, options: &RenderOptions) -> Vec<TextTable>;
+}
+
+fn to_csv(text_tables: &[TextTable]) -> Result<String> {
+    let mut csv_tables = vec![];
+    for text_table in text_tables {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.write_record(&text_table.headers)?;
+        for row in &text_table.rows {
+            writer.write_record(row)?;
+        }
+        csv_tables.push(String::from_utf8(writer.into_inner()?)?);
+    }
+    Ok(csv_tables.join("\n"))
+}
+
+/// Escapes text to fit in a cell of a Markdown table.
+pub fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn to_markdown(text_tables: &[TextTable]) -> String {
+    let mut markdown_tables = vec![];
+    for text_table in text_tables {
+        let mut markdown_table = format!("| {} |\n", text_table.headers.join(" | "));
+        markdown_table.push_str(&format!("|{}\n", " --- |".repeat(text_table.headers.len())));
+        for row in &text_table.rows {
+            let cells: Vec<String> = row.iter().map(|cell| markdown_cell(cell)).collect();
+            markdown_table.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        markdown_tables.push(markdown_table);
+    }
+    markdown_tables.join("\n")
+}
+
+/// Prints `output` in `output_format`.
+pub fn print_output<T: Render>(
+    output: &T,
+    output_format: &OutputFormat,
+    options: &RenderOptions,
+) -> Result<()> {
+    let document = Document::new(T::KIND, output, output.warnings());
+    match output_format {
+        OutputFormat::Table => output.print_tables(options),
+        OutputFormat::Json => println!("{}", serde_json::to_string(&document)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&document)?),
+        OutputFormat::Csv => print!("{}", to_csv(&output.text_tables(options))?),
+        OutputFormat::Markdown => print!("{}", to_markdown(&output.text_tables(options))),
+        OutputFormat::Template(template_path) => print!(
+            "{}",
+            Templates::from_file(OUTPUT_TEMPLATE, template_path)?
+                .render(OUTPUT_TEMPLATE, output)?
+        ),
+    }
+    Ok(())
+}
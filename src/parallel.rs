@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// How many requests are sent to GitHub at once, unless configured otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Calls `f` with every item on a pool of up to `concurrency` worker threads.
+///
+/// The results are returned in the order of `items`, however long each call takes.
+pub fn map_concurrently<T, R, F>(items: &[T], concurrency: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = concurrency.clamp(1, items.len().max(1));
+    let next_index = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().expect("worker panicked")[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .expect("worker panicked")
+        .into_iter()
+        .map(|result| result.expect("every item is mapped"))
+        .collect()
+}
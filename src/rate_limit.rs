@@ -0,0 +1,235 @@
+use crate::client::Client;
+use crate::error::Error;
+use serde_derive::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How many times a rate limited request is retried, unless configured otherwise.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// The delay before the first retry, doubled for every retry after it.
+///
+/// Only used when GitHub doesn't say when the rate limit resets.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest wait for a rate limit to reset before retrying.
+/// Requests whose rate limit resets later fail straight away.
+const MAX_RESET_WAIT: Duration = Duration::from_secs(60);
+
+/// How many points of the GraphQL rate limit can remain before warning about it.
+const LOW_RATE_LIMIT: u64 = 100;
+
+const RATE_LIMIT_FIELD: &str = "rateLimit { remaining resetAt cost }";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimit {
+    remaining: u64,
+    reset_at: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimitData {
+    rate_limit: Option<RateLimit>,
+}
+
+#[derive(Deserialize)]
+struct RateLimitError {
+    #[serde(rename = "type")]
+    error_type: Option<String>,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RateLimitResponse {
+    data: Option<RateLimitData>,
+    #[serde(default)]
+    errors: Vec<RateLimitError>,
+}
+
+/// Parses a timestamp like `2021-01-01T01:00:00Z`, as GitHub reports `resetAt` in.
+fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let date: Vec<i64> = date
+        .split('-')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let time: Vec<f64> = time
+        .split(':')
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    let (year, month, day, hours, minutes, seconds) = match (&date[..], &time[..]) {
+        ([year, month @ 1..=12, day @ 1..=31], [hours, minutes, seconds]) => {
+            (*year, *month, *day, hours, minutes, seconds)
+        }
+        _ => return None,
+    };
+
+    // Days from 1970-01-01 to the date, counting eras of 400 years that start in March.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days as f64 * 86_400.0 + hours * 3_600.0 + minutes * 60.0 + seconds;
+    Some(UNIX_EPOCH + Duration::try_from_secs_f64(seconds).ok()?)
+}
+
+/// When a rate limited HTTP request can be retried: after its `Retry-After` seconds,
+/// or at its `x-ratelimit-reset` in seconds since the epoch.
+pub fn reset_from_headers(
+    retry_after: Option<&str>,
+    ratelimit_reset: Option<&str>,
+) -> Option<SystemTime> {
+    let retry_after = retry_after
+        .and_then(|retry_after| retry_after.trim().parse().ok())
+        .map(|seconds| SystemTime::now() + Duration::from_secs(seconds));
+    let ratelimit_reset = ratelimit_reset
+        .and_then(|ratelimit_reset| ratelimit_reset.trim().parse().ok())
+        .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds));
+    retry_after.or(ratelimit_reset)
+}
+
+/// Fails a GraphQL response whose errors say it was rate limited, so it can be retried.
+///
+/// The error carries the `resetAt` of the rate limit, when the response has one.
+fn check_rate_limited(response: String) -> Result<String, Error> {
+    let rate_limit_response = match serde_json::from_str::<RateLimitResponse>(&response) {
+        Ok(rate_limit_response) => rate_limit_response,
+        Err(_) => return Ok(response),
+    };
+    let rate_limited_error = rate_limit_response
+        .errors
+        .into_iter()
+        .find(|error| error.error_type.as_deref() == Some("RATE_LIMITED"));
+    match rate_limited_error {
+        Some(error) => {
+            let reset_at = rate_limit_response
+                .data
+                .and_then(|data| data.rate_limit)
+                .and_then(|rate_limit| parse_timestamp(&rate_limit.reset_at));
+            Err(Error::RateLimited(error.message, reset_at))
+        }
+        None => Ok(response),
+    }
+}
+
+/// Adds the `rateLimit` field to the top level of a query.
+fn with_rate_limit(query: &str) -> String {
+    match query.find('{') {
+        Some(index) => format!(
+            "{} {}{}",
+            &query[..=index],
+            RATE_LIMIT_FIELD,
+            &query[index + 1..]
+        ),
+        None => query.to_string(),
+    }
+}
+
+/// A random delay of up to `max`, so retries from parallel requests don't line up.
+fn jitter(max: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|now| now.subsec_nanos())
+        .unwrap_or_default();
+    max.mul_f64(f64::from(nanos % 1000) / 1000.0)
+}
+
+/// Wraps a client to request the rate limit along with every query, and retry rate limited requests.
+///
+/// Rate limited requests are retried once the rate limit resets, when GitHub says when that is and
+/// it is soon enough, and otherwise fail straight away. When GitHub doesn't say, they are retried
+/// after an exponential backoff with jitter.
+/// A warning is printed once when the remaining rate limit runs low.
+pub struct RateLimitedClient {
+    client: Box<dyn Client>,
+    retries: u32,
+    warned: AtomicBool,
+}
+
+impl RateLimitedClient {
+    pub fn new(client: Box<dyn Client>, retries: u32) -> RateLimitedClient {
+        RateLimitedClient {
+            client,
+            retries,
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    fn with_retries<T>(&self, mut request: impl FnMut() -> Result<T, Error>) -> Result<T, Error> {
+        let mut attempt = 0;
+        loop {
+            match request() {
+                Err(Error::RateLimited(message, reset_at)) if attempt < self.retries => {
+                    let delay = match reset_at {
+                        Some(reset_at) => {
+                            let wait = reset_at
+                                .duration_since(SystemTime::now())
+                                .unwrap_or_default();
+                            if wait > MAX_RESET_WAIT {
+                                return Err(Error::RateLimited(message, Some(reset_at)));
+                            }
+                            wait + jitter(RETRY_DELAY)
+                        }
+                        None => RETRY_DELAY * 2u32.pow(attempt) + jitter(RETRY_DELAY),
+                    };
+                    eprintln!(
+                        "Rate limited by GitHub, retrying in {:.1}s: {}",
+                        delay.as_secs_f64(),
+                        message
+                    );
+                    sleep(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn check_rate_limit(&self, response: &str) {
+        let rate_limit = serde_json::from_str::<RateLimitResponse>(response)
+            .ok()
+            .and_then(|response| response.data)
+            .and_then(|data| data.rate_limit);
+        if let Some(rate_limit) = rate_limit {
+            if rate_limit.remaining < LOW_RATE_LIMIT && !self.warned.swap(true, Ordering::Relaxed) {
+                eprintln!(
+                    "Warning: only {} points of the GitHub API rate limit remain until {}",
+                    rate_limit.remaining, rate_limit.reset_at
+                );
+            }
+        }
+    }
+}
+
+impl Client for RateLimitedClient {
+    fn graphql(&self, query: &str) -> Result<String, Error> {
+        let query = with_rate_limit(query);
+        let response =
+            self.with_retries(|| self.client.graphql(&query).and_then(check_rate_limited))?;
+        self.check_rate_limit(&response);
+        Ok(response)
+    }
+
+    fn download_release_asset(
+        &self,
+        org: &str,
+        repo: &str,
+        tag: &str,
+        asset: &str,
+        dir: &Path,
+    ) -> Result<PathBuf, Error> {
+        self.with_retries(|| {
+            self.client
+                .download_release_asset(org, repo, tag, asset, dir)
+        })
+    }
+}
@@ -0,0 +1,107 @@
+use crate::interface::ShownInterface;
+use crate::model::{ModuleDetails, ModuleList};
+use crate::outdated::OutdatedModules;
+use anyhow::{bail, Result};
+use schemars::gen::SchemaSettings;
+use schemars::schema::{RootSchema, SchemaObject, SubschemaValidation};
+use schemars::JsonSchema;
+use serde_derive::Serialize;
+use std::str::FromStr;
+
+/// The version of the JSON output schema.
+///
+/// Bumped whenever fields are removed, renamed, or change meaning. New fields can be added without bumping it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// What a document describes, i.e. which command printed it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    /// A page of modules, printed by `ls`.
+    ModuleList,
+    /// A module with its tags and releases, printed by `ls <module>`.
+    Module,
+    /// Installed modules compared against their latest versions, printed by `outdated`.
+    Outdated,
+    /// The inputs and outputs of a module, printed by `show --inputs/--outputs`.
+    Interface,
+}
+
+impl FromStr for DocumentKind {
+    type Err = anyhow::Error;
+
+    fn from_str(kind: &str) -> Result<DocumentKind> {
+        match kind {
+            "module_list" => Ok(DocumentKind::ModuleList),
+            "module" => Ok(DocumentKind::Module),
+            "outdated" => Ok(DocumentKind::Outdated),
+            "interface" => Ok(DocumentKind::Interface),
+            _ => bail!(
+                "Unknown document kind {}. Use `module_list`, `module`, `outdated`, or `interface`.",
+                kind
+            ),
+        }
+    }
+}
+
+/// A JSON or YAML document printed by a command.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Document<'a, T> {
+    /// Version of the schema the document follows.
+    pub schema_version: u32,
+    pub kind: DocumentKind,
+    #[serde(flatten)]
+    pub data: &'a T,
+    /// Errors GitHub answered alongside the data, e.g. for repositories protected by SAML single sign-on.
+    pub warnings: Vec<String>,
+}
+
+impl<'a, T> Document<'a, T> {
+    pub fn new(kind: DocumentKind, data: &'a T, warnings: Vec<String>) -> Document<'a, T> {
+        Document {
+            schema_version: SCHEMA_VERSION,
+            kind,
+            data,
+            warnings,
+        }
+    }
+}
+
+/// The JSON Schema of the documents of `kind`, or of every document.
+pub fn output_schema(kind: Option<DocumentKind>) -> RootSchema {
+    let mut generator = SchemaSettings::draft07().into_generator();
+    let mut root_schema = match kind {
+        Some(DocumentKind::ModuleList) => generator.into_root_schema_for::<Document<ModuleList>>(),
+        Some(DocumentKind::Module) => generator.into_root_schema_for::<Document<ModuleDetails>>(),
+        Some(DocumentKind::Outdated) => {
+            generator.into_root_schema_for::<Document<OutdatedModules>>()
+        }
+        Some(DocumentKind::Interface) => {
+            generator.into_root_schema_for::<Document<ShownInterface>>()
+        }
+        None => {
+            let any_of = vec![
+                generator.subschema_for::<Document<ModuleList>>(),
+                generator.subschema_for::<Document<ModuleDetails>>(),
+                generator.subschema_for::<Document<OutdatedModules>>(),
+                generator.subschema_for::<Document<ShownInterface>>(),
+            ];
+            RootSchema {
+                meta_schema: generator.settings().meta_schema.clone(),
+                schema: SchemaObject {
+                    subschemas: Some(Box::new(SubschemaValidation {
+                        any_of: Some(any_of),
+                        ..SubschemaValidation::default()
+                    })),
+                    ..SchemaObject::default()
+                },
+                definitions: generator.take_definitions(),
+            }
+        }
+    };
+    root_schema.schema.metadata().title = Some(format!(
+        "gh-tf-mod output, schema version {}",
+        SCHEMA_VERSION
+    ));
+    root_schema
+}
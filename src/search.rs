@@ -0,0 +1,95 @@
+use crate::gh::{ListModulesResponse, ListModulesResponseNode};
+use std::cmp::Reverse;
+
+/// Parses a date like `2024-01-31`, the only format the `pushed` qualifier of GitHub search takes.
+pub fn parse_date(date: &str) -> Result<String, String> {
+    let invalid = || {
+        format!(
+            "Invalid date {}, expected YYYY-MM-DD, e.g. 2024-01-31",
+            date
+        )
+    };
+    let parts: Vec<&str> = date.split('-').collect();
+    let (year, month, day) = match parts[..] {
+        [year, month, day]
+            if year.len() == 4
+                && month.len() == 2
+                && day.len() == 2
+                && parts
+                    .iter()
+                    .all(|part| part.bytes().all(|b| b.is_ascii_digit())) =>
+        {
+            (
+                year.parse::<u32>().map_err(|_| invalid())?,
+                month.parse::<u32>().map_err(|_| invalid())?,
+                day.parse::<u32>().map_err(|_| invalid())?,
+            )
+        }
+        _ => return Err(invalid()),
+    };
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap_year => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if day == 0 || day > days_in_month {
+        return Err(invalid());
+    }
+    Ok(date.to_string())
+}
+
+/// How well a module matches the words of a search: by name first, then by topic, then by description.
+///
+/// Modules only matching by README score nothing.
+fn relevance(node: &ListModulesResponseNode, words: &[String]) -> u32 {
+    let name = node.name.to_lowercase();
+    let short_name = node
+        .short_name
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    let topics = node.repository_topics.names();
+    let description = node
+        .description
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    words
+        .iter()
+        .map(|word| {
+            let mut score = 0;
+            if short_name == *word {
+                score += 8;
+            }
+            if name.contains(word.as_str()) {
+                score += 4;
+            }
+            if topics.iter().any(|topic| topic.contains(word.as_str())) {
+                score += 2;
+            }
+            if description.contains(word.as_str()) {
+                score += 1;
+            }
+            score
+        })
+        .sum()
+}
+
+/// Sorts the modules found by a search for `text`, most relevant first.
+///
+/// Modules that are as relevant as each other are kept in the order GitHub ranked them in.
+pub fn rank_modules(search_modules_response: &mut ListModulesResponse, text: &str) {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.trim_matches('"').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    search_modules_response
+        .data
+        .search
+        .nodes
+        .sort_by_cached_key(|node| Reverse(relevance(node, &words)));
+}
@@ -0,0 +1,303 @@
+use crate::cache::Cache;
+use crate::client::Client;
+use crate::error::Error;
+use crate::gh::{get_release, ListModulePages, RELEASE_ASSET_NAME};
+use crate::naming::Naming;
+use crate::version::parse_tag;
+use anyhow::{anyhow, Result};
+use serde::ser;
+use serde_derive::{Deserialize, Serialize};
+use std::io::Cursor;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// The address the registry listens on, unless given otherwise.
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// Where the module registry protocol is served, as advertised by service discovery.
+const MODULES_PATH: &str = "/v1/modules/";
+
+#[derive(Serialize)]
+struct ServiceDiscovery {
+    #[serde(rename = "modules.v1")]
+    modules_v1: &'static str,
+}
+
+#[derive(Serialize)]
+struct ModuleVersion {
+    version: String,
+}
+
+#[derive(Serialize)]
+struct ModuleVersions {
+    versions: Vec<ModuleVersion>,
+}
+
+/// The response to `/versions`, which lists a single module.
+#[derive(Serialize)]
+struct VersionsResponse {
+    modules: Vec<ModuleVersions>,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    errors: Vec<String>,
+}
+
+/// A request of the module registry protocol.
+///
+/// The namespace of a module is the organization its repository is in.
+enum Route {
+    Discovery,
+    Versions {
+        org: String,
+        name: String,
+        provider: String,
+    },
+    Download {
+        org: String,
+        name: String,
+        provider: String,
+        version: String,
+    },
+}
+
+/// Whether a segment of a module path could be the name of an organization, repository, provider,
+/// or version, i.e. matches `^[A-Za-z0-9_.-]+$`.
+///
+/// Segments end up in GraphQL queries, so anything else is never looked up.
+fn is_valid_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.' | b'-'))
+}
+
+fn route(url: &str) -> Option<Route> {
+    let path = url.split('?').next().unwrap_or_default();
+    if path == "/.well-known/terraform.json" {
+        return Some(Route::Discovery);
+    }
+    let segments: Vec<&str> = path.strip_prefix(MODULES_PATH)?.split('/').collect();
+    if !segments.iter().all(|segment| is_valid_segment(segment)) {
+        return None;
+    }
+    match segments[..] {
+        [org, name, provider, "versions"] => Some(Route::Versions {
+            org: org.to_string(),
+            name: name.to_string(),
+            provider: provider.to_string(),
+        }),
+        [org, name, provider, version, "download"] => Some(Route::Download {
+            org: org.to_string(),
+            name: name.to_string(),
+            provider: provider.to_string(),
+            version: version.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// A version of a module, with the tag of the release it is downloaded from.
+#[derive(Serialize, Deserialize)]
+pub struct ReleaseVersion {
+    pub version: String,
+    pub tag: String,
+}
+
+/// The versions a module can be downloaded at, i.e. the tags of its releases that are semver versions.
+pub fn module_versions(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: String,
+    name: String,
+) -> Result<Vec<ReleaseVersion>> {
+    let list_module_response =
+        ListModulePages::new(client, naming, org, Some(provider), name, None, None)?.merge()?;
+    Ok(list_module_response
+        .data
+        .repository
+        .releases
+        .edges
+        .into_iter()
+        .filter_map(|release| {
+            let tag = release.node.tag.name;
+            parse_tag(&tag).map(|version| ReleaseVersion {
+                version: version.to_string(),
+                tag,
+            })
+        })
+        .collect())
+}
+
+/// Serves the module registry protocol for the modules of any organization, from their GitHub releases.
+pub struct Registry<'a> {
+    client: &'a dyn Client,
+    naming: &'a Naming,
+    cache: &'a Cache,
+    host: &'a str,
+}
+
+impl<'a> Registry<'a> {
+    pub fn new(
+        client: &'a dyn Client,
+        naming: &'a Naming,
+        cache: &'a Cache,
+        host: &'a str,
+    ) -> Registry<'a> {
+        Registry {
+            client,
+            naming,
+            cache,
+            host,
+        }
+    }
+
+    fn versions(&self, org: &str, provider: &str, name: &str) -> Result<Vec<ReleaseVersion>> {
+        self.cache.fetch(
+            self.host,
+            org,
+            &format!("serve versions {} {}", provider, name),
+            || {
+                module_versions(
+                    self.client,
+                    self.naming,
+                    org.to_string(),
+                    provider.to_string(),
+                    name.to_string(),
+                )
+            },
+        )
+    }
+
+    /// The URL of the `release.tar.gz` asset of the release of a module at `version`.
+    fn download_url(&self, org: &str, provider: &str, name: &str, version: &str) -> Result<String> {
+        let tag = self
+            .versions(org, provider, name)?
+            .into_iter()
+            .find(|release_version| release_version.version == version)
+            .map(|release_version| release_version.tag)
+            .ok_or_else(|| {
+                Error::NotFound(format!("Version {} not found for {}", version, name))
+            })?;
+        let repository = get_release(
+            self.client,
+            self.naming,
+            org.to_string(),
+            Some(provider.to_string()),
+            name.to_string(),
+            &tag,
+        )?
+        .data
+        .repository;
+        let asset = repository
+            .release
+            .and_then(|release| release.release_assets.nodes.into_iter().next());
+        match asset {
+            Some(asset) => Ok(asset.download_url),
+            None => Err(Error::NotFound(format!(
+                "{} not found in release {} of {}",
+                RELEASE_ASSET_NAME, tag, repository.name
+            ))
+            .into()),
+        }
+    }
+
+    fn respond(&self, route: Route) -> Result<Response<Cursor<Vec<u8>>>> {
+        match route {
+            Route::Discovery => Ok(json_response(
+                200,
+                &ServiceDiscovery {
+                    modules_v1: MODULES_PATH,
+                },
+            )),
+            Route::Versions {
+                org,
+                name,
+                provider,
+            } => {
+                let versions = self
+                    .versions(&org, &provider, &name)?
+                    .into_iter()
+                    .map(|release_version| ModuleVersion {
+                        version: release_version.version,
+                    })
+                    .collect();
+                Ok(json_response(
+                    200,
+                    &VersionsResponse {
+                        modules: vec![ModuleVersions { versions }],
+                    },
+                ))
+            }
+            Route::Download {
+                org,
+                name,
+                provider,
+                version,
+            } => {
+                let download_url = self.download_url(&org, &provider, &name, &version)?;
+                Ok(Response::from_data(vec![])
+                    .with_status_code(204)
+                    .with_header(header("X-Terraform-Get", &download_url)))
+            }
+        }
+    }
+
+    fn handle(&self, request: Request) {
+        let response = match (request.method(), route(request.url())) {
+            (Method::Get, Some(route)) => self.respond(route).unwrap_or_else(|err| {
+                let status = match err.downcast_ref::<Error>() {
+                    Some(Error::NotFound(_)) | Some(Error::NamingMismatch(_)) => 404,
+                    Some(Error::RateLimited(..)) => 429,
+                    _ => 500,
+                };
+                json_response(
+                    status,
+                    &ErrorResponse {
+                        errors: vec![format!("{:#}", err)],
+                    },
+                )
+            }),
+            _ => json_response(
+                404,
+                &ErrorResponse {
+                    errors: vec!["Not Found".to_string()],
+                },
+            ),
+        };
+        eprintln!(
+            "{} {} {}",
+            request.method(),
+            request.url(),
+            response.status_code().0
+        );
+        if let Err(err) = request.respond(response) {
+            eprintln!("Could not respond: {}", err);
+        }
+    }
+
+    /// Serves requests on `addr` until the process is stopped.
+    pub fn serve(&self, addr: &str) -> Result<()> {
+        let server =
+            Server::http(addr).map_err(|err| anyhow!("Could not listen on {}: {}", addr, err))?;
+        println!(
+            "Serving the module registry on http://{}",
+            server.server_addr()
+        );
+        for request in server.incoming_requests() {
+            self.handle(request);
+        }
+        Ok(())
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("Invalid header")
+}
+
+fn json_response<T: ser::Serialize>(status: u16, body: &T) -> Response<Cursor<Vec<u8>>> {
+    Response::from_data(serde_json::to_vec(body).unwrap_or_default())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+}
@@ -0,0 +1,97 @@
+use crate::client::Client;
+use crate::error::Error;
+use crate::gh::{get_module, GetModuleResponseRepository, README_FILE_NAME};
+use crate::install::resolve_version;
+use crate::naming::Naming;
+use crate::version::is_constraint;
+use anyhow::Result;
+use std::io::{stdout, IsTerminal};
+use termimad::MadSkin;
+
+/// The ref shown when no version is given, i.e. the default branch.
+pub const DEFAULT_REF: &str = "HEAD";
+
+/// A module, with its README at a ref.
+pub struct ModuleReadme {
+    pub repository: GetModuleResponseRepository,
+    pub git_ref: String,
+    pub readme: String,
+}
+
+/// The ref to show a module at for `version`, which can be a tag or a version constraint.
+///
+/// Without a version, the default branch is shown.
+pub fn resolve_ref(
+    client: &dyn Client,
+    naming: &Naming,
+    org: &str,
+    provider: &Option<String>,
+    module: &str,
+    version: Option<String>,
+) -> Result<String> {
+    match version {
+        Some(ref version) if is_constraint(version) => resolve_version(
+            client,
+            naming,
+            org.to_string(),
+            provider.clone(),
+            module.to_string(),
+            version,
+            false,
+        ),
+        Some(version) => Ok(version),
+        None => Ok(DEFAULT_REF.to_string()),
+    }
+}
+
+/// Gets a module with its README at `git_ref`.
+pub fn get_module_readme(
+    client: &dyn Client,
+    naming: &Naming,
+    org: String,
+    provider: Option<String>,
+    module: String,
+    git_ref: String,
+) -> Result<ModuleReadme> {
+    let mut repository = get_module(client, naming, org, provider, module, &git_ref)?
+        .data
+        .repository;
+    let readme = match repository.readme.take().and_then(|readme| readme.text) {
+        Some(readme) => readme,
+        None => {
+            return Err(Error::NotFound(format!(
+                "No {} found in {} at {}",
+                README_FILE_NAME, repository.name, git_ref
+            ))
+            .into())
+        }
+    };
+    Ok(ModuleReadme {
+        repository,
+        git_ref,
+        readme,
+    })
+}
+
+/// Prints the README of a module, rendered for the terminal unless `raw`.
+///
+/// Colors are left out with `no_color`, or when the output isn't a terminal.
+pub fn print_module_readme(module_readme: &ModuleReadme, raw: bool, no_color: bool) {
+    if raw {
+        print!("{}", module_readme.readme);
+        return;
+    }
+
+    let repository = &module_readme.repository;
+    let skin = if no_color || !stdout().is_terminal() {
+        MadSkin::no_style()
+    } else {
+        MadSkin::default()
+    };
+    println!("{} ({})", repository.name, module_readme.git_ref);
+    if let Some(ref description) = repository.description {
+        println!("{}", description);
+    }
+    println!("{}\n", repository.url);
+    skin.print_text(&module_readme.readme);
+}
@@ -0,0 +1,188 @@
+use crate::client::Client;
+use crate::gh::find_module_repo;
+use crate::install::{resolve_latest_version, resolve_version, ModuleRequest};
+use crate::interface::get_module_interface;
+use crate::lock::{module_key, Lockfile};
+use crate::naming::Naming;
+use crate::version::is_constraint;
+use anyhow::{bail, Result};
+use serde_derive::Serialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Where the `source` of a snippet points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceStyle {
+    /// `git::https://<host>/<org>/<repo>.git?ref=<tag>`
+    Https,
+    /// `git::ssh://git@<host>/<org>/<repo>.git?ref=<tag>`
+    Ssh,
+    /// The directory the module is installed into, e.g. `./modules/aws/s3`.
+    Path,
+}
+
+impl FromStr for SourceStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(source_style: &str) -> Result<SourceStyle> {
+        match source_style {
+            "https" => Ok(SourceStyle::Https),
+            "ssh" => Ok(SourceStyle::Ssh),
+            "path" => Ok(SourceStyle::Path),
+            _ => bail!(
+                "Unknown source style {}. Use `https`, `ssh`, or `path`.",
+                source_style
+            ),
+        }
+    }
+}
+
+/// A required input of a snippet, with a placeholder value.
+#[derive(Debug, Serialize)]
+pub struct SnippetInput {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub input_type: Option<String>,
+    pub description: Option<String>,
+    pub value: String,
+}
+
+/// The data a snippet template is rendered with.
+#[derive(Debug, Serialize)]
+pub struct Snippet {
+    pub name: String,
+    pub provider: String,
+    pub org: String,
+    pub repo: String,
+    pub tag: String,
+    pub source: String,
+    pub vendored: bool,
+    pub inputs: Vec<SnippetInput>,
+}
+
+/// A placeholder for an input of `input_type`, valid HCL for the usual types.
+fn placeholder(input_type: Option<&str>) -> String {
+    let input_type = input_type.unwrap_or_default();
+    if input_type == "string" {
+        "\"\"".to_string()
+    } else if input_type == "number" {
+        "0".to_string()
+    } else if input_type == "bool" {
+        "false".to_string()
+    } else if input_type.starts_with("list")
+        || input_type.starts_with("set")
+        || input_type.starts_with("tuple")
+    {
+        "[]".to_string()
+    } else if input_type.starts_with("map") || input_type.starts_with("object") {
+        "{}".to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// A path to a vendored module, as Terraform expects local sources: starting with `./` or `../`, or absolute.
+fn local_source(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if path.starts_with("./") || path.starts_with("../") || path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("./{}", path)
+    }
+}
+
+/// The tag of a module to pin a snippet to: the one matching its version, or the highest semver release.
+fn resolve_tag(client: &dyn Client, naming: &Naming, request: &ModuleRequest) -> Result<String> {
+    match request.version {
+        Some(ref version) if is_constraint(version) => resolve_version(
+            client,
+            naming,
+            request.org.clone(),
+            request.provider.clone(),
+            request.module.clone(),
+            version,
+            request.prerelease,
+        ),
+        Some(ref version) => Ok(version.clone()),
+        None => resolve_latest_version(
+            client,
+            naming,
+            request.org.clone(),
+            request.provider.clone(),
+            request.module.clone(),
+            request.prerelease,
+        ),
+    }
+}
+
+/// Builds a snippet for a module at the requested version, with the required inputs declared at that tag.
+///
+/// Modules with a `path` source point at their path in the lockfile, or at `<modules-dir>/<provider>/<name>`.
+pub fn build_snippet(
+    client: &dyn Client,
+    naming: &Naming,
+    request: ModuleRequest,
+    host: &str,
+    source_style: SourceStyle,
+    modules_dir: &Path,
+) -> Result<Snippet> {
+    let module_repo = find_module_repo(
+        client,
+        naming,
+        &request.org,
+        request.provider.clone(),
+        &request.module,
+    )?;
+    let tag = resolve_tag(client, naming, &request)?;
+    let ModuleRequest {
+        org,
+        provider,
+        module,
+        ..
+    } = request;
+    let interface = get_module_interface(client, naming, org.clone(), provider, module, &tag)?;
+
+    let source = match source_style {
+        SourceStyle::Https => format!(
+            "git::https://{}/{}/{}.git?ref={}",
+            host, org, module_repo.name, tag
+        ),
+        SourceStyle::Ssh => format!(
+            "git::ssh://git@{}/{}/{}.git?ref={}",
+            host, org, module_repo.name, tag
+        ),
+        SourceStyle::Path => {
+            let key = module_key(&module_repo.provider, &module_repo.short_name);
+            let module_path = match Lockfile::load()?.modules.get(&key) {
+                Some(locked_module) => PathBuf::from(&locked_module.path),
+                None => modules_dir
+                    .join(&module_repo.provider)
+                    .join(&module_repo.short_name),
+            };
+            local_source(&module_path)
+        }
+    };
+
+    let inputs = interface
+        .inputs
+        .into_iter()
+        .filter(|input| input.required)
+        .map(|input| SnippetInput {
+            value: placeholder(input.input_type.as_deref()),
+            name: input.name,
+            input_type: input.input_type,
+            description: input.description,
+        })
+        .collect();
+
+    Ok(Snippet {
+        name: module_repo.short_name,
+        provider: module_repo.provider,
+        org,
+        repo: module_repo.name,
+        tag,
+        source,
+        vendored: source_style == SourceStyle::Path,
+        inputs,
+    })
+}
@@ -0,0 +1,112 @@
+use crate::client::Client;
+use crate::config::Config;
+use crate::install::{
+    install_module, remove_module, split_module_provider, Destination, ModuleRequest,
+};
+use crate::lock::{module_key, LockedModule, Lockfile};
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::fs::metadata;
+use std::path::{Path, PathBuf};
+
+/// A change made to the working tree while syncing it to the manifest.
+pub enum SyncAction {
+    Installed(LockedModule),
+    UpToDate(LockedModule),
+    Removed(LockedModule),
+}
+
+/// Installs the modules declared in the `modules` section of the config file,
+/// and removes installed modules that are no longer declared.
+///
+/// Modules already installed with the declared version and path are left alone,
+/// or reinstalled from the lockfile if their directory is missing.
+pub fn sync_manifest(
+    client: &dyn Client,
+    config: &Config,
+    lockfile: &mut Lockfile,
+    prerelease: bool,
+) -> Result<Vec<SyncAction>> {
+    let naming = config.naming()?;
+    let modules_dir = PathBuf::from(config.modules_dir.clone().unwrap_or_default());
+    let manifest_modules = config.modules.clone().unwrap_or_default();
+
+    let mut actions = vec![];
+    let mut declared_keys = BTreeSet::new();
+    for (module, manifest_module) in manifest_modules {
+        let provider = manifest_module
+            .provider
+            .clone()
+            .or_else(|| config.provider.clone());
+        let (provider, name) = split_module_provider(provider, &module)?;
+        let key = module_key(&provider, &name);
+        declared_keys.insert(key.clone());
+
+        let module_path = match manifest_module.path {
+            Some(ref path) => PathBuf::from(path),
+            None => modules_dir.join(&provider).join(&name),
+        };
+
+        if let Some(locked_module) = lockfile.modules.get(&key).cloned() {
+            let locked_path = PathBuf::from(&locked_module.path);
+            if locked_module.version == manifest_module.version && locked_path == module_path {
+                if metadata(&locked_path).is_ok() {
+                    actions.push(SyncAction::UpToDate(locked_module));
+                } else {
+                    let installed_module = install_module(
+                        client,
+                        &naming,
+                        ModuleRequest::for_locked_module(&locked_module, locked_module.tag.clone()),
+                        Destination::Path(&locked_path),
+                        Some(&locked_module),
+                    )?;
+                    actions.push(SyncAction::Installed(installed_module));
+                }
+                continue;
+            }
+            if locked_path != module_path && metadata(&locked_path).is_ok() {
+                remove_module(&locked_path, false)?;
+            }
+        }
+
+        let org = manifest_module
+            .org
+            .clone()
+            .map_or_else(|| config.org(client), Ok)?;
+        let installed_module = install_module(
+            client,
+            &naming,
+            ModuleRequest {
+                org,
+                provider: Some(provider),
+                module: name,
+                version: manifest_module.version.clone(),
+                prerelease,
+            },
+            Destination::Path(&module_path),
+            None,
+        )?;
+        lockfile.insert(installed_module.clone());
+        lockfile.save()?;
+        actions.push(SyncAction::Installed(installed_module));
+    }
+
+    let undeclared_keys: Vec<String> = lockfile
+        .modules
+        .keys()
+        .filter(|key| !declared_keys.contains(*key))
+        .cloned()
+        .collect();
+    for key in undeclared_keys {
+        if let Some(locked_module) = lockfile.modules.remove(&key) {
+            let locked_path = Path::new(&locked_module.path);
+            if metadata(locked_path).is_ok() {
+                remove_module(locked_path, false)?;
+            }
+            lockfile.save()?;
+            actions.push(SyncAction::Removed(locked_module));
+        }
+    }
+
+    Ok(actions)
+}
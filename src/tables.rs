@@ -1,8 +1,6 @@
-use crate::gh::{
-    ListModuleResponse, ListModuleResponseRefs, ListModuleResponseRefsPageInfo,
-    ListModuleResponseReleases, ListModuleResponseReleasesPageInfo, ListModulesResponse,
-    ListModulesResponsePageInfo,
-};
+use crate::interface::{ModuleInput, ModuleOutput, INPUT_COLUMNS};
+use crate::model::{ModuleDetails, ModuleList, Page, Release, Tag};
+use crate::outdated::OutdatedModule;
 use prettytable::{color, Attr, Cell, Row, Table};
 
 fn add_modules_header(
@@ -85,7 +83,7 @@ fn add_modules_header(
 fn print_modules_paging_info(
     total_count: u64,
     filtered_repos: u64,
-    page_info: &ListModulesResponsePageInfo,
+    next_cursor: &Option<String>,
     no_color: bool,
 ) {
     let use_color = !no_color;
@@ -118,7 +116,7 @@ fn print_modules_paging_info(
         };
         page_info_titles_vec.push(filtered_repos_header);
     }
-    if page_info.has_next_page {
+    if next_cursor.is_some() {
         let end_cursor_header_value = "End Cursor";
         let end_cursor_header = if use_color {
             Cell::new(end_cursor_header_value)
@@ -141,12 +139,11 @@ fn print_modules_paging_info(
         };
         page_info_vec.push(filtered_repo_cell);
     }
-    if page_info.has_next_page {
-        let end_cursor = &page_info.end_cursor.clone().unwrap_or_default();
+    if let Some(end_cursor) = next_cursor {
         let end_cursor_cell = if use_color {
-            Cell::new(&end_cursor).with_style(Attr::ForegroundColor(color::GREEN))
+            Cell::new(end_cursor).with_style(Attr::ForegroundColor(color::GREEN))
         } else {
-            Cell::new(&end_cursor)
+            Cell::new(end_cursor)
         };
         page_info_vec.push(end_cursor_cell);
     }
@@ -156,7 +153,7 @@ fn print_modules_paging_info(
 }
 
 pub fn print_modules_table(
-    list_modules_response: ListModulesResponse,
+    module_list: &ModuleList,
     no_color: bool,
     description: bool,
     url: bool,
@@ -167,12 +164,12 @@ pub fn print_modules_table(
 
     let mut table = Table::new();
     add_modules_header(&mut table, no_color, description, url, tags, releases);
-    for module in list_modules_response.data.search.nodes {
+    for module in &module_list.modules {
         let mut row = Row::empty();
-        row.add_cell(Cell::new(&module.short_name.unwrap_or(module.name)));
-        row.add_cell(Cell::new(&module.provider.unwrap_or_default()));
+        row.add_cell(Cell::new(&module.name));
+        row.add_cell(Cell::new(&module.provider));
         if description {
-            row.add_cell(Cell::new(&module.description.unwrap_or_default()));
+            row.add_cell(Cell::new(module.description.as_deref().unwrap_or_default()));
         }
         if url {
             let url_cell = if use_color {
@@ -183,32 +180,20 @@ pub fn print_modules_table(
             row.add_cell(url_cell);
         }
         if tags {
-            let latest_tag_name = if module.refs.nodes.is_empty() {
-                "".to_string()
-            } else {
-                module.refs.nodes[0].name.clone()
-            };
-            row.add_cell(Cell::new(&latest_tag_name));
+            row.add_cell(Cell::new(module.latest_tag.as_deref().unwrap_or_default()));
         }
         if releases {
-            let latest_release_name = if module.releases.nodes.is_empty() {
-                "".to_string()
-            } else {
-                module.releases.nodes[0].name.clone()
-            };
-            row.add_cell(Cell::new(&latest_release_name));
+            row.add_cell(Cell::new(
+                module.latest_release.as_deref().unwrap_or_default(),
+            ));
         }
         table.add_row(row);
     }
     table.printstd();
     print_modules_paging_info(
-        list_modules_response.data.search.repository_count,
-        list_modules_response
-            .data
-            .search
-            .filtered_repository_count
-            .unwrap_or(0),
-        &list_modules_response.data.search.page_info,
+        module_list.pagination.total_count,
+        module_list.hidden_count,
+        &module_list.pagination.next_cursor,
         no_color,
     );
 }
@@ -318,11 +303,7 @@ fn add_tags_header(table: &mut Table, no_color: bool, url: bool) {
     table.set_titles(Row::new(title_vec));
 }
 
-fn print_tags_paging_info(
-    total_count: u64,
-    page_info: &ListModuleResponseRefsPageInfo,
-    no_color: bool,
-) {
+fn print_tags_paging_info(total_count: u64, next_cursor: &Option<String>, no_color: bool) {
     let use_color = !no_color;
 
     let mut page_info_table = Table::new();
@@ -339,7 +320,7 @@ fn print_tags_paging_info(
     };
     page_info_titles_vec.push(tags_total_header);
 
-    if page_info.has_next_page {
+    if next_cursor.is_some() {
         let end_cursor_header_value = "End Cursor";
         let end_cursor_header = if use_color {
             Cell::new(end_cursor_header_value)
@@ -357,8 +338,7 @@ fn print_tags_paging_info(
     let total_count_cell = Cell::new(&total_count_text);
     let mut page_info_vec = vec![total_count_cell];
 
-    if page_info.has_next_page {
-        let end_cursor = &page_info.end_cursor.clone().unwrap_or_default();
+    if let Some(end_cursor) = next_cursor {
         let end_cursor_cell = if use_color {
             Cell::new(end_cursor).with_style(Attr::ForegroundColor(color::GREEN))
         } else {
@@ -372,26 +352,29 @@ fn print_tags_paging_info(
     page_info_table.printstd();
 }
 
-fn print_tags_table(tags: ListModuleResponseRefs, no_color: bool, url: bool) {
+fn print_tags_table(tags: &Page<Tag>, no_color: bool, url: bool) {
     let use_color = !no_color;
     let mut table = Table::new();
     add_tags_header(&mut table, no_color, url);
-    for tag in tags.edges {
+    for tag in &tags.items {
         let mut row = Row::empty();
-        row.add_cell(Cell::new(&tag.node.name));
+        row.add_cell(Cell::new(&tag.name));
         if url {
             let url_cell = if use_color {
-                Cell::new(&tag.node.target.commit_url)
-                    .with_style(Attr::ForegroundColor(color::BLUE))
+                Cell::new(&tag.commit_url).with_style(Attr::ForegroundColor(color::BLUE))
             } else {
-                Cell::new(&tag.node.target.commit_url)
+                Cell::new(&tag.commit_url)
             };
             row.add_cell(url_cell);
         }
         table.add_row(row);
     }
     table.printstd();
-    print_tags_paging_info(tags.total_count, &tags.page_info, no_color);
+    print_tags_paging_info(
+        tags.pagination.total_count,
+        &tags.pagination.next_cursor,
+        no_color,
+    );
 }
 
 fn add_releases_header(table: &mut Table, no_color: bool, url: bool, tags: bool) {
@@ -433,11 +416,7 @@ fn add_releases_header(table: &mut Table, no_color: bool, url: bool, tags: bool)
     table.set_titles(Row::new(title_vec));
 }
 
-fn print_releases_paging_info(
-    total_count: u64,
-    page_info: &ListModuleResponseReleasesPageInfo,
-    no_color: bool,
-) {
+fn print_releases_paging_info(total_count: u64, next_cursor: &Option<String>, no_color: bool) {
     let use_color = !no_color;
 
     let mut page_info_table = Table::new();
@@ -454,7 +433,7 @@ fn print_releases_paging_info(
     };
     page_info_titles_vec.push(releases_total_header);
 
-    if page_info.has_next_page {
+    if next_cursor.is_some() {
         let end_cursor_header_value = "End Cursor";
         let end_cursor_header = if use_color {
             Cell::new(end_cursor_header_value)
@@ -472,8 +451,7 @@ fn print_releases_paging_info(
     let total_count_cell = Cell::new(&total_count_text);
     let mut page_info_vec = vec![total_count_cell];
 
-    if page_info.has_next_page {
-        let end_cursor = &page_info.end_cursor.clone().unwrap_or_default();
+    if let Some(end_cursor) = next_cursor {
         let end_cursor_cell = if use_color {
             Cell::new(end_cursor).with_style(Attr::ForegroundColor(color::GREEN))
         } else {
@@ -487,52 +465,47 @@ fn print_releases_paging_info(
     page_info_table.printstd();
 }
 
-fn print_releases_table(
-    releases: ListModuleResponseReleases,
-    no_color: bool,
-    url: bool,
-    tags: bool,
-) {
+fn print_releases_table(releases: &Page<Release>, no_color: bool, url: bool, tags: bool) {
     let use_color = !no_color;
     let mut table = Table::new();
     add_releases_header(&mut table, no_color, url, tags);
-    for release in releases.edges {
+    for release in &releases.items {
         let mut row = Row::empty();
-        row.add_cell(Cell::new(&release.node.name));
+        row.add_cell(Cell::new(&release.name));
         if tags {
-            let tags_cell = Cell::new(&release.node.tag.name);
+            let tags_cell = Cell::new(&release.tag);
             row.add_cell(tags_cell);
         }
         if url {
             let url_cell = if use_color {
-                Cell::new(&release.node.url).with_style(Attr::ForegroundColor(color::BLUE))
+                Cell::new(&release.url).with_style(Attr::ForegroundColor(color::BLUE))
             } else {
-                Cell::new(&release.node.url)
+                Cell::new(&release.url)
             };
             row.add_cell(url_cell);
         }
         table.add_row(row);
     }
     table.printstd();
-    print_releases_paging_info(releases.total_count, &releases.page_info, no_color);
+    print_releases_paging_info(
+        releases.pagination.total_count,
+        &releases.pagination.next_cursor,
+        no_color,
+    );
 }
 
 pub fn print_module_table(
-    list_module_response: ListModuleResponse,
+    module_details: &ModuleDetails,
     no_color: bool,
     description: bool,
     url: bool,
     tags: bool,
     releases: bool,
 ) {
+    let module = &module_details.module;
     let mut table = Table::new();
-    let tags_is_empty = list_module_response.data.repository.refs.edges.is_empty();
-    let releases_is_empty = list_module_response
-        .data
-        .repository
-        .releases
-        .edges
-        .is_empty();
+    let tags_is_empty = module_details.tags.items.is_empty();
+    let releases_is_empty = module_details.releases.items.is_empty();
     add_module_header(
         &mut table,
         no_color,
@@ -541,57 +514,132 @@ pub fn print_module_table(
         tags_is_empty,
         releases_is_empty,
     );
-    let mut module_vec = vec![
-        Cell::new(
-            &list_module_response
-                .data
-                .repository
-                .short_name
-                .unwrap_or_default(),
-        ),
-        Cell::new(
-            &list_module_response
-                .data
-                .repository
-                .provider
-                .unwrap_or_default(),
-        ),
-    ];
+    let mut module_vec = vec![Cell::new(&module.name), Cell::new(&module.provider)];
     if description {
-        module_vec.push(Cell::new(
-            &list_module_response
-                .data
-                .repository
-                .description
-                .unwrap_or_default(),
-        ));
+        module_vec.push(Cell::new(module.description.as_deref().unwrap_or_default()));
     }
     if url {
-        module_vec.push(Cell::new(&list_module_response.data.repository.url));
+        module_vec.push(Cell::new(&module.url));
     }
-    if !tags_is_empty {
-        module_vec.push(Cell::new(
-            &list_module_response.data.repository.refs.edges[0].node.name,
-        ));
+    if let Some(ref latest_tag) = module.latest_tag {
+        module_vec.push(Cell::new(latest_tag));
     }
-    if !releases_is_empty {
-        module_vec.push(Cell::new(
-            &list_module_response.data.repository.releases.edges[0]
-                .node
-                .name,
-        ));
+    if let Some(ref latest_release) = module.latest_release {
+        module_vec.push(Cell::new(latest_release));
     }
     table.add_row(Row::new(module_vec));
     table.printstd();
     if tags && !tags_is_empty {
-        print_tags_table(list_module_response.data.repository.refs, no_color, url);
+        print_tags_table(&module_details.tags, no_color, url);
     }
     if releases && !releases_is_empty {
-        print_releases_table(
-            list_module_response.data.repository.releases,
-            no_color,
-            url,
-            tags,
-        );
+        print_releases_table(&module_details.releases, no_color, url, tags);
+    }
+}
+
+fn add_outdated_header(table: &mut Table, no_color: bool) {
+    let use_color = !no_color;
+
+    let mut title_vec = vec![];
+    for header_value in ["Name", "Provider", "Current", "Wanted", "Latest"] {
+        let header = if use_color {
+            Cell::new(header_value)
+                .with_style(Attr::Bold)
+                .with_style(Attr::ForegroundColor(color::CYAN))
+        } else {
+            Cell::new(header_value).with_style(Attr::Bold)
+        };
+        title_vec.push(header);
+    }
+
+    table.set_titles(Row::new(title_vec));
+}
+
+pub fn print_outdated_table(outdated_modules: &[OutdatedModule], no_color: bool) {
+    let use_color = !no_color;
+
+    let mut table = Table::new();
+    add_outdated_header(&mut table, no_color);
+    for outdated_module in outdated_modules {
+        let mut row = Row::empty();
+        row.add_cell(Cell::new(&outdated_module.name));
+        row.add_cell(Cell::new(&outdated_module.provider));
+        let current_cell = if use_color && outdated_module.outdated {
+            Cell::new(&outdated_module.current).with_style(Attr::ForegroundColor(color::RED))
+        } else {
+            Cell::new(&outdated_module.current)
+        };
+        row.add_cell(current_cell);
+        let wanted = outdated_module.wanted.clone().unwrap_or_default();
+        let wanted_cell = if use_color && wanted != outdated_module.current {
+            Cell::new(&wanted).with_style(Attr::ForegroundColor(color::GREEN))
+        } else {
+            Cell::new(&wanted)
+        };
+        row.add_cell(wanted_cell);
+        let latest = outdated_module.latest.clone().unwrap_or_default();
+        let latest_cell = if use_color && outdated_module.outdated {
+            Cell::new(&latest).with_style(Attr::ForegroundColor(color::YELLOW))
+        } else {
+            Cell::new(&latest)
+        };
+        row.add_cell(latest_cell);
+        table.add_row(row);
+    }
+    table.printstd();
+}
+
+fn add_interface_header(table: &mut Table, no_color: bool, header_values: &[&str]) {
+    let use_color = !no_color;
+
+    let mut title_vec = vec![];
+    for header_value in header_values {
+        let header = if use_color {
+            Cell::new(header_value)
+                .with_style(Attr::Bold)
+                .with_style(Attr::ForegroundColor(color::CYAN))
+        } else {
+            Cell::new(header_value).with_style(Attr::Bold)
+        };
+        title_vec.push(header);
+    }
+
+    table.set_titles(Row::new(title_vec));
+}
+
+pub fn print_inputs_table(inputs: &[ModuleInput], no_color: bool) {
+    let use_color = !no_color;
+
+    let mut table = Table::new();
+    add_interface_header(&mut table, no_color, INPUT_COLUMNS);
+    for input in inputs {
+        let mut row = Row::empty();
+        for (index, cell) in input.cells().iter().enumerate() {
+            // Required inputs stand out by their name.
+            if index == 0 && use_color && input.required {
+                row.add_cell(Cell::new(cell).with_style(Attr::ForegroundColor(color::YELLOW)));
+            } else {
+                row.add_cell(Cell::new(cell));
+            }
+        }
+        table.add_row(row);
+    }
+    table.printstd();
+}
+
+pub fn print_outputs_table(outputs: &[ModuleOutput], no_color: bool) {
+    let mut table = Table::new();
+    add_interface_header(
+        &mut table,
+        no_color,
+        &["Output", "Description", "Sensitive"],
+    );
+    for output in outputs {
+        let mut row = Row::empty();
+        row.add_cell(Cell::new(&output.name));
+        row.add_cell(Cell::new(&output.description.clone().unwrap_or_default()));
+        row.add_cell(Cell::new(if output.sensitive { "yes" } else { "" }));
+        table.add_row(row);
     }
+    table.printstd();
 }
@@ -0,0 +1,118 @@
+use crate::interface::format_hcl;
+use crate::output::markdown_cell;
+use anyhow::{Context, Result};
+use handlebars::{handlebars_helper, no_escape, Handlebars, JsonValue};
+use serde::ser;
+use std::fs::{metadata, read_to_string};
+use std::path::Path;
+
+/// Where templates overriding the built-in ones are looked up, unless configured otherwise.
+pub const DEFAULT_TEMPLATES_DIR: &str = ".config/gh-tf-mod/templates";
+
+/// The template of `snippet`.
+pub const SNIPPET_TEMPLATE: &str = "snippet";
+
+/// The template of the index page of `catalog`.
+pub const CATALOG_INDEX_TEMPLATE: &str = "catalog-index";
+
+/// The template of every module page of `catalog`.
+pub const CATALOG_MODULE_TEMPLATE: &str = "catalog-module";
+
+const BUILT_IN_TEMPLATES: &[(&str, &str)] = &[
+    (SNIPPET_TEMPLATE, include_str!("templates/snippet.hbs")),
+    (
+        CATALOG_INDEX_TEMPLATE,
+        include_str!("templates/catalog-index.hbs"),
+    ),
+    (
+        CATALOG_MODULE_TEMPLATE,
+        include_str!("templates/catalog-module.hbs"),
+    ),
+];
+
+// `{{markdown_cell description}}` escapes text to fit in a cell of a Markdown table.
+handlebars_helper!(markdown_cell_helper: |value: JsonValue| match value {
+    JsonValue::Null => String::new(),
+    JsonValue::String(text) => markdown_cell(&text),
+    value => markdown_cell(&value.to_string()),
+});
+
+// `{{prefix_lines description "  # "}}` starts every line of text with a prefix, e.g. to comment it out.
+handlebars_helper!(prefix_lines_helper: |text: str, prefix: str| text
+    .lines()
+    .map(|line| if line.is_empty() {
+        prefix.trim_end().to_string()
+    } else {
+        format!("{}{}", prefix, line)
+    })
+    .collect::<Vec<_>>()
+    .join("\n"));
+
+// `{{hcl default}}` prints a value as HCL, e.g. the default of an input.
+handlebars_helper!(hcl_helper: |value: JsonValue| match value {
+    JsonValue::Null => String::new(),
+    value => hcl::to_value(value)
+        .map(|value| format_hcl(&value))
+        .unwrap_or_default(),
+});
+
+/// Handlebars templates, built in or overridden by the user.
+///
+/// Templates render text like HCL and Markdown, so nothing is HTML-escaped.
+/// Besides the built-in helpers of Handlebars, `markdown_cell`, `prefix_lines`, and `hcl` can be used.
+pub struct Templates {
+    handlebars: Handlebars<'static>,
+}
+
+impl Templates {
+    fn new() -> Templates {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_escape_fn(no_escape);
+        handlebars.set_strict_mode(true);
+        handlebars.register_helper("markdown_cell", Box::new(markdown_cell_helper));
+        handlebars.register_helper("prefix_lines", Box::new(prefix_lines_helper));
+        handlebars.register_helper("hcl", Box::new(hcl_helper));
+        Templates { handlebars }
+    }
+
+    /// Loads the built-in templates, each overridden by `<name>.hbs` in `templates_dir` when it exists.
+    pub fn load(templates_dir: &Path) -> Result<Templates> {
+        let mut templates = Templates::new();
+        for (name, template) in BUILT_IN_TEMPLATES {
+            let template_path = templates_dir.join(format!("{}.hbs", name));
+            if metadata(&template_path).is_ok() {
+                templates.override_template(name, &template_path)?;
+            } else {
+                templates
+                    .handlebars
+                    .register_template_string(name, template)
+                    .with_context(|| format!("Could not parse the built-in {} template", name))?;
+            }
+        }
+        Ok(templates)
+    }
+
+    /// Loads only the template in `template_path`, as `name`.
+    pub fn from_file(name: &str, template_path: &Path) -> Result<Templates> {
+        let mut templates = Templates::new();
+        templates.override_template(name, template_path)?;
+        Ok(templates)
+    }
+
+    /// Replaces the template `name` with the one in `template_path`.
+    pub fn override_template(&mut self, name: &str, template_path: &Path) -> Result<()> {
+        let template = read_to_string(template_path)
+            .with_context(|| format!("Could not read {}", template_path.to_string_lossy()))?;
+        self.handlebars
+            .register_template_string(name, template)
+            .with_context(|| format!("Could not parse {}", template_path.to_string_lossy()))?;
+        Ok(())
+    }
+
+    /// Renders the template `name` with `data`.
+    pub fn render<T: ser::Serialize>(&self, name: &str, data: &T) -> Result<String> {
+        self.handlebars
+            .render(name, data)
+            .with_context(|| format!("Could not render the {} template", name))
+    }
+}
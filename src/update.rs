@@ -0,0 +1,99 @@
+use crate::client::Client;
+use crate::install::{install_module, Destination, ModuleRequest};
+use crate::lock::{LockedModule, Lockfile, LOCKFILE};
+use crate::naming::Naming;
+use crate::outdated::{check_module, is_older};
+use crate::parallel::map_concurrently;
+use crate::version::{is_constraint, parse_tag};
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+/// A module update, applied or planned.
+pub struct ModuleUpdate {
+    pub key: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// The constraint to record for a module updated to `tag`.
+///
+/// Constraints that no longer match after crossing a major version are replaced by `~> <major>.<minor>`.
+fn updated_constraint(locked_module: &LockedModule, tag: &str, major: bool) -> Option<String> {
+    match (&locked_module.version, parse_tag(tag)) {
+        (Some(version), Some(updated)) if major && is_constraint(version) => {
+            Some(format!("~> {}.{}", updated.major, updated.minor))
+        }
+        _ => locked_module.version.clone(),
+    }
+}
+
+/// Updates modules in the lockfile to the newest version their constraints allow.
+///
+/// With `major`, modules are updated to their latest version even when it crosses a major version.
+/// Modules pinned to a tag are left alone either way.
+/// With `dry_run`, the updates are only planned, and nothing is installed.
+///
+/// Up to `concurrency` modules are checked at once, and the updates are then installed one at a time.
+pub fn update_modules(
+    client: &dyn Client,
+    naming: &Naming,
+    lockfile: &mut Lockfile,
+    modules: &[String],
+    major: bool,
+    dry_run: bool,
+    concurrency: usize,
+) -> Result<Vec<ModuleUpdate>> {
+    let locked_modules = if modules.is_empty() {
+        lockfile.modules.values().cloned().collect::<Vec<_>>()
+    } else {
+        modules
+            .iter()
+            .map(|module| {
+                lockfile
+                    .modules
+                    .get(module)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("{} is not in {}", module, LOCKFILE))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let outdated_modules = map_concurrently(&locked_modules, concurrency, |locked_module| {
+        check_module(client, naming, locked_module)
+    });
+
+    let mut updates = vec![];
+    for (locked_module, outdated_module) in locked_modules.into_iter().zip(outdated_modules) {
+        let outdated_module = outdated_module?;
+        let pinned = matches!(&locked_module.version, Some(version) if !is_constraint(version));
+        let target = if major && !pinned {
+            outdated_module.latest
+        } else {
+            outdated_module.wanted
+        };
+        let tag = match target {
+            Some(tag) if is_older(&locked_module.tag, &tag) => tag,
+            _ => continue,
+        };
+
+        if !dry_run {
+            let mut updated_module = install_module(
+                client,
+                naming,
+                ModuleRequest::for_locked_module(&locked_module, tag.clone()),
+                Destination::Path(Path::new(&locked_module.path)),
+                None,
+            )?;
+            updated_module.version = updated_constraint(&locked_module, &tag, major);
+            lockfile.insert(updated_module);
+            lockfile.save()?;
+        }
+
+        updates.push(ModuleUpdate {
+            key: locked_module.key(),
+            from: locked_module.tag,
+            to: tag,
+        });
+    }
+    Ok(updates)
+}
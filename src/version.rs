@@ -0,0 +1,190 @@
+use anyhow::{anyhow, bail, Result};
+use semver::{BuildMetadata, Version};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Pessimistic,
+}
+
+/// A single Terraform version constraint, e.g. `~> 2.1`.
+#[derive(Debug, Clone)]
+struct Constraint {
+    operator: Operator,
+    version: Version,
+    segments: usize,
+}
+
+impl Constraint {
+    fn matches(&self, version: &Version) -> bool {
+        match self.operator {
+            Operator::Equal => version == &self.version,
+            Operator::NotEqual => version != &self.version,
+            Operator::GreaterThan => version > &self.version,
+            Operator::GreaterThanOrEqual => version >= &self.version,
+            Operator::LessThan => version < &self.version,
+            Operator::LessThanOrEqual => version <= &self.version,
+            Operator::Pessimistic => {
+                let upper_bound = match self.segments {
+                    1 | 2 => Version::new(self.version.major + 1, 0, 0),
+                    _ => Version::new(self.version.major, self.version.minor + 1, 0),
+                };
+                version >= &self.version && version < &upper_bound
+            }
+        }
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = anyhow::Error;
+
+    fn from_str(constraint: &str) -> Result<Constraint> {
+        let constraint = constraint.trim();
+        let operators = [
+            ("~>", Operator::Pessimistic),
+            (">=", Operator::GreaterThanOrEqual),
+            ("<=", Operator::LessThanOrEqual),
+            ("!=", Operator::NotEqual),
+            (">", Operator::GreaterThan),
+            ("<", Operator::LessThan),
+            ("=", Operator::Equal),
+        ];
+        let (operator, version) = operators
+            .iter()
+            .find_map(|(prefix, operator)| {
+                constraint
+                    .strip_prefix(prefix)
+                    .map(|version| (*operator, version))
+            })
+            .unwrap_or((Operator::Equal, constraint));
+
+        let version = version.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let (release, prerelease) = match version.split_once('-') {
+            Some((release, prerelease)) => (release, Some(prerelease)),
+            None => (version, None),
+        };
+        let segments = release.split('.').count();
+        if release.is_empty() || segments > 3 {
+            bail!("Invalid version constraint \"{}\"", constraint);
+        }
+        let padded_release = match segments {
+            1 => format!("{}.0.0", release),
+            2 => format!("{}.0", release),
+            _ => release.to_string(),
+        };
+        let padded_version = match prerelease {
+            Some(prerelease) => format!("{}-{}", padded_release, prerelease),
+            None => padded_release,
+        };
+        let version = Version::parse(&padded_version)
+            .map_err(|_| anyhow!("Invalid version constraint \"{}\"", constraint))?;
+
+        Ok(Constraint {
+            operator,
+            version,
+            segments,
+        })
+    }
+}
+
+/// A comma separated set of Terraform version constraints, e.g. `>= 1.0, < 3.0`.
+///
+/// A version must match every constraint in the set.
+#[derive(Debug, Clone)]
+pub struct Constraints {
+    constraints: Vec<Constraint>,
+}
+
+impl Constraints {
+    /// Constraints that match any release.
+    pub fn any() -> Constraints {
+        Constraints {
+            constraints: vec![],
+        }
+    }
+
+    /// Checks whether `version` satisfies every constraint.
+    ///
+    /// Prereleases only match when `prerelease` is set, or when a constraint pins them exactly.
+    pub fn matches(&self, version: &Version, prerelease: bool) -> bool {
+        if !version.pre.is_empty() && !prerelease {
+            let pinned = self.constraints.iter().any(|constraint| {
+                constraint.operator == Operator::Equal && &constraint.version == version
+            });
+            if !pinned {
+                return false;
+            }
+        }
+        self.constraints
+            .iter()
+            .all(|constraint| constraint.matches(version))
+    }
+}
+
+impl FromStr for Constraints {
+    type Err = anyhow::Error;
+
+    fn from_str(constraints: &str) -> Result<Constraints> {
+        let constraints = constraints
+            .split(',')
+            .map(Constraint::from_str)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Constraints { constraints })
+    }
+}
+
+/// Checks whether a module version is a constraint to resolve rather than a tag to install as is.
+pub fn is_constraint(version: &str) -> bool {
+    version.contains(['~', '>', '<', '=', '!', ','])
+}
+
+/// Parses a tag like `v1.2.0` or `1.2.0` as a semver version, ignoring build metadata.
+pub fn parse_tag(tag: &str) -> Option<Version> {
+    let mut version = Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()?;
+    version.build = BuildMetadata::EMPTY;
+    Some(version)
+}
+
+/// The tag a set of constraints resolved to, along with the tags that could not be considered.
+pub struct Resolution {
+    pub tag: Option<String>,
+    pub version: Option<Version>,
+    pub invalid_tags: Vec<String>,
+}
+
+/// Finds the tag with the highest version that satisfies `constraints`.
+pub fn resolve(constraints: &Constraints, tags: &[String], prerelease: bool) -> Resolution {
+    let mut resolution = Resolution {
+        tag: None,
+        version: None,
+        invalid_tags: vec![],
+    };
+    for tag in tags {
+        let version = match parse_tag(tag) {
+            Some(version) => version,
+            None => {
+                resolution.invalid_tags.push(tag.clone());
+                continue;
+            }
+        };
+        if !constraints.matches(&version, prerelease) {
+            continue;
+        }
+        if resolution
+            .version
+            .as_ref()
+            .is_none_or(|resolved_version| &version > resolved_version)
+        {
+            resolution.tag = Some(tag.clone());
+            resolution.version = Some(version);
+        }
+    }
+    resolution
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+
+fn list_module_names(fake_gh: &FakeGh, args: &[&str]) -> Result<Vec<String>> {
+    let output = fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--json"])
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    Ok(response["modules"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|module| module["repo"].as_str().unwrap_or_default().to_string())
+        .collect())
+}
+
+#[test]
+fn list_modules_from_cache() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "in:name user:org",
+        &search_response(&[("terraform-aws-s3-module", &[])], None),
+    )?;
+    assert_eq!(
+        list_module_names(&fake_gh, &[])?,
+        vec!["terraform-aws-s3-module"]
+    );
+
+    fake_gh.respond(
+        "in:name user:org",
+        &search_response(&[("terraform-aws-vpc-module", &[])], None),
+    )?;
+    assert_eq!(
+        list_module_names(&fake_gh, &[])?,
+        vec!["terraform-aws-s3-module"]
+    );
+    assert_eq!(
+        list_module_names(&fake_gh, &["--refresh"])?,
+        vec!["terraform-aws-vpc-module"]
+    );
+    assert_eq!(
+        list_module_names(&fake_gh, &["--offline"])?,
+        vec!["terraform-aws-vpc-module"]
+    );
+    Ok(())
+}
+
+#[test]
+fn offline_without_cached_response() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "in:name user:org",
+        &search_response(&[("terraform-aws-s3-module", &[])], None),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--offline"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Nothing is cached"));
+    Ok(())
+}
+
+#[test]
+fn offline_without_gh_or_token() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "in:name user:org",
+        &search_response(&[("terraform-aws-s3-module", &[])], None),
+    )?;
+    list_module_names(&fake_gh, &[])?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--json", "--offline"])
+        .env("PATH", fake_gh.project_file("no-bin"))
+        .env_remove("GH_TOKEN")
+        .env_remove("GITHUB_TOKEN")
+        .env("GH_CONFIG_DIR", fake_gh.project_file("no-gh-config"))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("terraform-aws-s3-module"));
+    Ok(())
+}
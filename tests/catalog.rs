@@ -0,0 +1,169 @@
+mod common;
+
+use anyhow::Result;
+use common::{files_response, search_response, FakeGh};
+use std::fs;
+
+const VARIABLES_TF: &str = r#"
+variable "name" {
+  type        = string
+  description = "Name of the bucket | the queue"
+}
+
+variable "tags" {
+  type    = map(string)
+  default = { team = "platform" }
+}
+"#;
+
+const OUTPUTS_TF: &str = r#"
+output "arn" {
+  value       = aws_s3_bucket.this.arn
+  description = "ARN of the bucket"
+}
+"#;
+
+fn respond_with_modules(fake_gh: &FakeGh) -> Result<()> {
+    let mut first_page = search_response(&[("terraform-aws-s3-module", &[])], Some("page-1"));
+    first_page["data"]["search"]["nodes"][0]["description"] = "An S3 bucket".into();
+    first_page["data"]["search"]["nodes"][0]["releases"]["nodes"] = serde_json::json!([{
+        "name": "v1.2.0",
+        "tagName": "1.2.0",
+        "publishedAt": "2024-01-01T00:00:00Z"
+    }]);
+    fake_gh.respond("after: null", &first_page)?;
+    fake_gh.respond(
+        "after: \"page-1\"",
+        &search_response(&[("terraform-aws-vpc-module", &[])], None),
+    )?;
+    fake_gh.respond(
+        "1.2.0:",
+        &files_response(
+            "terraform-aws-s3-module",
+            &[
+                ("README.md", "# S3\n\nCreates a bucket.\n"),
+                ("outputs.tf", OUTPUTS_TF),
+                ("variables.tf", VARIABLES_TF),
+            ],
+        ),
+    )?;
+    fake_gh.respond(
+        "HEAD:",
+        &files_response("terraform-aws-vpc-module", &[("main.tf", "")]),
+    )
+}
+
+#[test]
+fn catalog_writes_index_and_module_pages() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+
+    fake_gh
+        .command()?
+        .args(["catalog", "--org", "org", "--out", "docs"])
+        .assert()
+        .success()
+        .stdout("Wrote 2 modules to docs\n");
+
+    let index = fs::read_to_string(fake_gh.project_file("docs/index.md"))?;
+    assert!(index.contains("# Terraform Modules of org\n"));
+    assert!(index.contains("| [s3](aws-s3.md) | aws | v1.2.0 | An S3 bucket |\n"));
+    assert!(index.contains("| [vpc](aws-vpc.md) | aws |  |  |\n"));
+
+    let s3 = fs::read_to_string(fake_gh.project_file("docs/aws-s3.md"))?;
+    assert!(s3.contains(
+        "- Latest release: [v1.2.0](https://github.com/org/terraform-aws-s3-module/releases/tag/1.2.0)\n"
+    ));
+    assert!(s3.contains("| `name` | string |  | yes | Name of the bucket \\| the queue |\n"));
+    assert!(s3.contains("| `tags` | map(string) | { \"team\" = \"platform\" } | no |  |\n"));
+    assert!(s3.contains("| `arn` | ARN of the bucket |\n"));
+    assert!(s3.contains("## README\n\n# S3\n\nCreates a bucket.\n"));
+
+    let vpc = fs::read_to_string(fake_gh.project_file("docs/aws-vpc.md"))?;
+    assert!(vpc.contains("- Latest release: none\n"));
+    assert!(vpc.contains("No inputs.\n"));
+    assert!(!vpc.contains("## README"));
+    Ok(())
+}
+
+#[test]
+fn catalog_with_overridden_templates() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+    let templates_dir = fake_gh.project_file(".config/gh-tf-mod/templates");
+    fs::create_dir_all(&templates_dir)?;
+    fs::write(
+        templates_dir.join("catalog-index.hbs"),
+        "<ul>{{#each modules}}<li><a href=\"{{page}}\">{{name}}</a></li>{{/each}}</ul>\n",
+    )?;
+    fs::write(
+        templates_dir.join("catalog-module.hbs"),
+        "<h1>{{org}}/{{name}}</h1>\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args([
+            "catalog",
+            "--org",
+            "org",
+            "--out",
+            "site",
+            "--extension",
+            "html",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("site/index.html"))?,
+        "<ul><li><a href=\"aws-s3.html\">s3</a></li><li><a href=\"aws-vpc.html\">vpc</a></li></ul>\n"
+    );
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("site/aws-s3.html"))?,
+        "<h1>org/s3</h1>\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn catalog_keeps_going_past_broken_modules() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+    fake_gh.respond(
+        "1.2.0:",
+        &files_response(
+            "terraform-aws-s3-module",
+            &[
+                ("README.md", "# S3\n"),
+                ("variables.tf", "variable \"name\" {\n"),
+            ],
+        ),
+    )?;
+    let mut empty_repo = files_response("terraform-aws-vpc-module", &[]);
+    empty_repo["data"]["repository"]["files"] = serde_json::Value::Null;
+    fake_gh.respond("HEAD:", &empty_repo)?;
+
+    fake_gh
+        .command()?
+        .args(["catalog", "--org", "org", "--out", "docs"])
+        .assert()
+        .success()
+        .stdout("Wrote 2 modules to docs\n")
+        .stderr(predicates::str::contains(
+            "Could not parse variables.tf",
+        ))
+        .stderr(predicates::str::contains(
+            "cataloging terraform-aws-s3-module without an interface",
+        ))
+        .stderr(predicates::str::contains(
+            "HEAD not found in terraform-aws-vpc-module, cataloging terraform-aws-vpc-module without a README or interface",
+        ));
+
+    let s3 = fs::read_to_string(fake_gh.project_file("docs/aws-s3.md"))?;
+    assert!(s3.contains("No inputs.\n"));
+    assert!(s3.contains("## README\n\n# S3\n"));
+    let vpc = fs::read_to_string(fake_gh.project_file("docs/aws-vpc.md"))?;
+    assert!(vpc.contains("No inputs.\n"));
+    Ok(())
+}
@@ -0,0 +1,291 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+use assert_cmd::cargo::CommandCargoExt;
+use assert_cmd::Command;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+use tempfile::TempDir;
+
+const FAKE_GH: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+FIXTURES="$(cd "$(dirname "$0")/.." && pwd)"
+
+case "$1" in
+--version)
+    echo "gh version 2.0.0 (fake)"
+    ;;
+api)
+    query="$*"
+    while IFS=$'\t' read -r pattern response; do
+        if [[ "$query" == *"$pattern"* && -f "$FIXTURES/responses/$response" ]]; then
+            if [[ "$response" == *.err ]]; then
+                cat "$FIXTURES/responses/$response" >&2
+                if [ -f "$FIXTURES/responses/$response.once" ]; then
+                    rm "$FIXTURES/responses/$response"
+                fi
+                exit 1
+            fi
+            cat "$FIXTURES/responses/$response"
+            exit 0
+        fi
+    done < "$FIXTURES/routes"
+    echo "fake gh: no response for query: $query" >&2
+    exit 1
+    ;;
+release)
+    tag="$3"
+    dir=""
+    while [ $# -gt 0 ]; do
+        if [ "$1" = "--dir" ]; then
+            dir="$2"
+        fi
+        shift
+    done
+    cp "$FIXTURES/assets/$tag/release.tar.gz" "$dir/release.tar.gz"
+    ;;
+*)
+    echo "fake gh: unsupported command: $*" >&2
+    exit 1
+    ;;
+esac
+"#;
+
+/// A lockfile with `aws-s3` installed at 2.1.0 with the constraint `~> 2.1`.
+pub const LOCKFILE: &str = "---
+modules:
+  aws-s3:
+    org: org
+    repo: terraform-aws-s3-module
+    provider: aws
+    name: s3
+    version: ~> 2.1
+    tag: 2.1.0
+    asset_url: https://github.com/org/terraform-aws-s3-module/releases/download/2.1.0/release.tar.gz
+    commit: 0123456789abcdef0123456789abcdef01234567
+    sha256: 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+    path: modules/aws/s3
+";
+
+/// A fake `gh` binary serving canned GraphQL responses and release assets.
+pub struct FakeGh {
+    pub root: TempDir,
+    pub project: TempDir,
+}
+
+impl FakeGh {
+    pub fn new() -> Result<FakeGh> {
+        let root = tempfile::tempdir()?;
+        fs::create_dir_all(root.path().join("bin"))?;
+        fs::create_dir_all(root.path().join("responses"))?;
+        fs::create_dir_all(root.path().join("assets"))?;
+        File::create(root.path().join("routes"))?;
+
+        let gh_path = root.path().join("bin").join("gh");
+        fs::write(&gh_path, FAKE_GH)?;
+        fs::set_permissions(&gh_path, fs::Permissions::from_mode(0o755))?;
+
+        Ok(FakeGh {
+            root,
+            project: tempfile::tempdir()?,
+        })
+    }
+
+    /// Responds to any `gh api` call containing `pattern` with `response`.
+    ///
+    /// Later responses take precedence over earlier ones.
+    pub fn respond(&self, pattern: &str, response: &serde_json::Value) -> Result<()> {
+        let responses_dir = self.root.path().join("responses");
+        let response_file = format!("{}.json", fs::read_dir(&responses_dir)?.count());
+        fs::write(responses_dir.join(&response_file), response.to_string())?;
+        let routes_path = self.root.path().join("routes");
+        let routes = fs::read_to_string(&routes_path)?;
+        fs::write(
+            &routes_path,
+            format!("{}\t{}\n{}", pattern, response_file, routes),
+        )?;
+        Ok(())
+    }
+
+    /// Fails any `gh api` call containing `pattern`, printing `message` to stderr like `gh` does.
+    pub fn fail(&self, pattern: &str, message: &str) -> Result<()> {
+        let responses_dir = self.root.path().join("responses");
+        let response_file = format!("{}.err", fs::read_dir(&responses_dir)?.count());
+        fs::write(responses_dir.join(&response_file), message)?;
+        let routes_path = self.root.path().join("routes");
+        let routes = fs::read_to_string(&routes_path)?;
+        fs::write(
+            &routes_path,
+            format!("{}\t{}\n{}", pattern, response_file, routes),
+        )?;
+        Ok(())
+    }
+
+    /// Fails the next `gh api` call containing `pattern`, then falls through to earlier responses.
+    pub fn fail_once(&self, pattern: &str, message: &str) -> Result<()> {
+        self.fail(pattern, message)?;
+        let responses_dir = self.root.path().join("responses");
+        let response_file = format!("{}.err", fs::read_dir(&responses_dir)?.count() - 1);
+        fs::write(responses_dir.join(format!("{}.once", response_file)), "")?;
+        Ok(())
+    }
+
+    /// Publishes a `release.tar.gz` asset for `tag` containing `files`, returning its size.
+    pub fn release_asset(&self, tag: &str, files: &[(&str, &str)]) -> Result<u64> {
+        let asset_dir = self.root.path().join("assets").join(tag);
+        fs::create_dir_all(&asset_dir)?;
+        let asset_path = asset_dir.join("release.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            File::create(&asset_path)?,
+            Compression::default(),
+        ));
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, contents.as_bytes())?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(fs::metadata(&asset_path)?.len())
+    }
+
+    /// The path of the `release.tar.gz` asset published for `tag`.
+    pub fn release_asset_path(&self, tag: &str) -> PathBuf {
+        self.root
+            .path()
+            .join("assets")
+            .join(tag)
+            .join("release.tar.gz")
+    }
+
+    pub fn project_path(&self) -> &Path {
+        self.project.path()
+    }
+
+    pub fn project_file(&self, path: &str) -> PathBuf {
+        self.project.path().join(path)
+    }
+
+    /// Runs `gh-tf-mod` in the project directory, with the fake `gh` first on the `PATH`,
+    /// and a cache dir of its own.
+    pub fn command(&self) -> Result<Command> {
+        Ok(Command::from_std(self.std_command()?))
+    }
+
+    /// Like [`FakeGh::command`], for commands that keep running, like `serve`.
+    pub fn std_command(&self) -> Result<process::Command> {
+        let path = format!(
+            "{}:{}",
+            self.root.path().join("bin").to_string_lossy(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let mut command = process::Command::cargo_bin("gh-tf-mod")?;
+        command
+            .current_dir(self.project.path())
+            .env("PATH", path)
+            .env("XDG_CACHE_HOME", self.root.path().join("cache"));
+        Ok(command)
+    }
+}
+
+pub fn release_response(repo: &str, tag: &str, size: u64) -> serde_json::Value {
+    serde_json::json!({
+        "data": {
+            "repository": {
+                "name": repo,
+                "url": format!("https://github.com/org/{}", repo),
+                "release": {
+                    "name": tag,
+                    "tagName": tag,
+                    "url": format!("https://github.com/org/{}/releases/tag/{}", repo, tag),
+                    "tagCommit": {
+                        "oid": "0123456789abcdef0123456789abcdef01234567"
+                    },
+                    "releaseAssets": {
+                        "nodes": [
+                            {
+                                "name": "release.tar.gz",
+                                "downloadUrl": format!("https://github.com/org/{}/releases/download/{}/release.tar.gz", repo, tag),
+                                "size": size
+                            }
+                        ]
+                    }
+                }
+            }
+        }
+    })
+}
+
+pub fn tags_response(tags: &[&str]) -> serde_json::Value {
+    let nodes: Vec<serde_json::Value> = tags
+        .iter()
+        .map(|tag| serde_json::json!({ "name": tag }))
+        .collect();
+    serde_json::json!({
+        "data": {
+            "repository": {
+                "refs": {
+                    "nodes": nodes,
+                    "pageInfo": {
+                        "endCursor": null,
+                        "hasNextPage": false
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// The files at the root of `repo`, with an `examples` directory besides them.
+pub fn files_response(repo: &str, files: &[(&str, &str)]) -> serde_json::Value {
+    let mut entries =
+        vec![serde_json::json!({ "name": "examples", "type": "tree", "object": null })];
+    entries.extend(files.iter().map(|(name, text)| {
+        serde_json::json!({ "name": name, "type": "blob", "object": { "text": text } })
+    }));
+    serde_json::json!({
+        "data": {
+            "repository": {
+                "name": repo,
+                "url": format!("https://github.com/org/{}", repo),
+                "files": { "entries": entries }
+            }
+        }
+    })
+}
+
+/// A page of search results listing `repos` with their topics, followed by another page after `end_cursor`.
+pub fn search_response(repos: &[(&str, &[&str])], end_cursor: Option<&str>) -> serde_json::Value {
+    let nodes: Vec<serde_json::Value> = repos
+        .iter()
+        .map(|(repo, topics)| {
+            let topics: Vec<serde_json::Value> = topics
+                .iter()
+                .map(|topic| serde_json::json!({ "topic": { "name": topic } }))
+                .collect();
+            serde_json::json!({
+                "name": repo,
+                "description": null,
+                "url": format!("https://github.com/org/{}", repo),
+                "repositoryTopics": { "nodes": topics },
+                "releases": { "nodes": [] },
+                "refs": { "nodes": [] }
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "data": {
+            "search": {
+                "pageInfo": { "hasNextPage": end_cursor.is_some(), "endCursor": end_cursor },
+                "repositoryCount": repos.len(),
+                "nodes": nodes
+            }
+        }
+    })
+}
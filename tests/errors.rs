@@ -0,0 +1,128 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+use std::fs;
+
+#[test]
+fn not_logged_in() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.fail(
+        "graphql",
+        "To get started with GitHub CLI, please run:  gh auth login",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org"])
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains("Not authenticated with GitHub"));
+    Ok(())
+}
+
+#[test]
+fn module_not_found() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.fail(
+        "terraform-aws-foo-module",
+        "GraphQL: Could not resolve to a Repository with the name 'org/terraform-aws-foo-module'. (repository)",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-foo", "--org", "org"])
+        .assert()
+        .code(4);
+    Ok(())
+}
+
+#[test]
+fn module_without_provider() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "s3", "--org", "org"])
+        .assert()
+        .code(6)
+        .stderr(predicates::str::contains(
+            "Could not determine the provider of s3",
+        ));
+    Ok(())
+}
+
+#[test]
+fn rate_limited() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.fail("graphql", "GraphQL: API rate limit exceeded for user ID 1.")?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nretries: 0\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org"])
+        .assert()
+        .code(5);
+    Ok(())
+}
+
+#[test]
+fn module_not_found_in_graphql_errors() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "terraform-aws-foo-module",
+        &serde_json::json!({
+            "data": { "repository": null },
+            "errors": [{
+                "type": "NOT_FOUND",
+                "path": ["repository"],
+                "message": "Could not resolve to a Repository with the name 'org/terraform-aws-foo-module'."
+            }]
+        }),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "aws-foo", "--org", "org"])
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains(
+            "Module terraform-aws-foo-module not found in org",
+        ));
+    Ok(())
+}
+
+#[test]
+fn partial_results_with_warnings() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let mut response = search_response(&[("terraform-aws-s3-module", &[])], None);
+    response["data"]["search"]["nodes"]
+        .as_array_mut()
+        .unwrap()
+        .insert(0, serde_json::Value::Null);
+    response["errors"] = serde_json::json!([{
+        "type": "FORBIDDEN",
+        "path": ["search", "nodes", 0],
+        "message": "Resource protected by organization SAML enforcement."
+    }]);
+    fake_gh.respond("in:name user:org", &response)?;
+
+    let output = fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?
+        .contains("Warning: Resource protected by organization SAML enforcement."));
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(response["modules"][0]["repo"], "terraform-aws-s3-module");
+    assert_eq!(
+        response["warnings"][0],
+        "Resource protected by organization SAML enforcement."
+    );
+    Ok(())
+}
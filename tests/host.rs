@@ -0,0 +1,57 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh};
+use std::fs;
+
+#[test]
+fn install_from_hostname() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "--hostname ghe.example.com",
+        &release_response("terraform-aws-s3-module", "1.2.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.2.0"]))?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "--org", "org"])
+        .assert()
+        .failure();
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "--org", "org"])
+        .args(["--hostname", "ghe.example.com"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3"
+    );
+    Ok(())
+}
+
+#[test]
+fn install_from_configured_host() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "--hostname ghe.example.com",
+        &release_response("terraform-aws-s3-module", "1.2.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.2.0"]))?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nhost: ghe.example.com\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .assert()
+        .success();
+    Ok(())
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh};
+use std::fs;
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+/// Serves the GraphQL and REST endpoints used to install a module, for requests with a token.
+fn serve_github_api(fake_gh: &FakeGh, tag: &str, size: u64) -> Result<String> {
+    let server = Server::http("127.0.0.1:0").map_err(|err| anyhow::anyhow!(err))?;
+    let api_url = format!("http://{}", server.server_addr());
+    let release = release_response("terraform-aws-s3-module", tag, size).to_string();
+    let tags = tags_response(&[tag]).to_string();
+    let release_tags_path = format!("/repos/org/terraform-aws-s3-module/releases/tags/{}", tag);
+    let assets = serde_json::json!({
+        "assets": [{ "name": "release.tar.gz", "url": format!("{}/assets/1", api_url) }]
+    })
+    .to_string();
+    let asset = fs::read(fake_gh.release_asset_path(tag))?;
+
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            let _ = request.as_reader().read_to_string(&mut body);
+            let authorized = request.headers().iter().any(|header| {
+                header.field.equiv("Authorization") && header.value == "bearer test-token"
+            });
+            let response = match (authorized, request.url()) {
+                (false, _) => Response::from_string("Bad credentials").with_status_code(401),
+                (true, "/graphql") if body.contains("refs(refPrefix") => {
+                    Response::from_string(tags.clone())
+                }
+                (true, "/graphql") => Response::from_string(release.clone()),
+                (true, "/assets/1") => Response::from_data(asset.clone()),
+                (true, url) if url == release_tags_path => Response::from_string(assets.clone()),
+                (true, _) => Response::from_string("Not Found").with_status_code(404),
+            };
+            let response = response
+                .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+            let _ = request.respond(response);
+        }
+    });
+
+    Ok(api_url)
+}
+
+#[test]
+fn install_with_http_client() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    let api_url = serve_github_api(&fake_gh, "1.2.0", size)?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nclient: http\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .env("GITHUB_API_URL", &api_url)
+        .env("GH_TOKEN", "test-token")
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3"
+    );
+    Ok(())
+}
+
+#[test]
+fn http_client_reports_bad_credentials() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    let api_url = serve_github_api(&fake_gh, "1.2.0", size)?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nclient: http\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .env("GITHUB_API_URL", &api_url)
+        .env("GH_TOKEN", "wrong-token")
+        .assert()
+        .code(3)
+        .stderr(predicates::str::contains("Bad credentials"));
+    Ok(())
+}
+
+#[test]
+fn http_client_uses_enterprise_token_for_host() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    let api_url = serve_github_api(&fake_gh, "1.2.0", size)?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nclient: http\nhost: ghe.example.com\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .env("GITHUB_API_URL", &api_url)
+        .env("GH_TOKEN", "wrong-token")
+        .env("GH_ENTERPRISE_TOKEN", "test-token")
+        .assert()
+        .success();
+    Ok(())
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh};
+use std::fs;
+
+#[test]
+fn install_unpacks_latest_release() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.2.0\")",
+        &release_response("terraform-aws-s3-module", "1.2.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.2.0"]))?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "-o", "org"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3"
+    );
+    Ok(())
+}
+
+#[test]
+fn install_pinned_version_into_modules_dir() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.0.0", &[("main.tf", "# s3 1.0.0")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.0.0\")",
+        &release_response("terraform-aws-s3-module", "1.0.0", size),
+    )?;
+
+    fake_gh
+        .command()?
+        .args([
+            "install", "s3@1.0.0", "-o", "org", "-p", "aws", "-m", "vendor",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("vendor/aws/s3/main.tf"))?,
+        "# s3 1.0.0"
+    );
+    Ok(())
+}
+
+#[test]
+fn install_rejects_asset_with_unexpected_size() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.2.0\")",
+        &release_response("terraform-aws-s3-module", "1.2.0", size + 1),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.2.0"]))?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "-o", "org"])
+        .assert()
+        .failure();
+
+    assert!(!fake_gh.project_file("modules/aws/s3").exists());
+    Ok(())
+}
+
+#[test]
+fn install_without_version_picks_highest_semver_tag() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("2.0.0", &[("main.tf", "# s3 2.0.0")])?;
+    fake_gh.respond(
+        "release(tagName: \"2.0.0\")",
+        &release_response("terraform-aws-s3-module", "2.0.0", size),
+    )?;
+    fake_gh.respond(
+        "refs(refPrefix",
+        &tags_response(&["1.3.0", "2.1.0-rc.1", "2.0.0", "latest"]),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "-o", "org"])
+        .assert()
+        .success();
+    fake_gh.command()?.args(["outdated"]).assert().success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3 2.0.0"
+    );
+    Ok(())
+}
+
+#[test]
+fn reinstall_replaces_installed_copy_without_leftovers() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    for (tag, files) in [
+        (
+            "1.0.0",
+            &[("main.tf", "# s3 1.0.0"), ("old.tf", "# old")][..],
+        ),
+        ("1.2.0", &[("main.tf", "# s3 1.2.0")][..]),
+    ] {
+        let size = fake_gh.release_asset(tag, files)?;
+        fake_gh.respond(
+            &format!("release(tagName: \"{}\")", tag),
+            &release_response("terraform-aws-s3-module", tag, size),
+        )?;
+    }
+
+    for spec in ["aws-s3@1.0.0", "aws-s3@1.2.0"] {
+        fake_gh
+            .command()?
+            .args(["install", spec, "-o", "org"])
+            .assert()
+            .success();
+    }
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3 1.2.0"
+    );
+    assert!(!fake_gh.project_file("modules/aws/s3/old.tf").exists());
+    let entries: Vec<_> = fs::read_dir(fake_gh.project_file("modules/aws"))?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(entries, ["s3"]);
+    Ok(())
+}
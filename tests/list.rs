@@ -0,0 +1,157 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+
+fn module_page(
+    releases: &[&str],
+    releases_cursor: Option<&str>,
+    tags: &[&str],
+    tags_cursor: Option<&str>,
+) -> serde_json::Value {
+    let release_edges: Vec<serde_json::Value> = releases
+        .iter()
+        .map(
+            |tag| serde_json::json!({ "node": { "name": tag, "url": "", "tag": { "name": tag } } }),
+        )
+        .collect();
+    let tag_edges: Vec<serde_json::Value> = tags
+        .iter()
+        .map(|tag| serde_json::json!({ "node": { "name": tag, "target": { "commitUrl": "" } } }))
+        .collect();
+    serde_json::json!({
+        "data": {
+            "repository": {
+                "name": "terraform-aws-s3-module",
+                "description": null,
+                "url": "https://github.com/org/terraform-aws-s3-module",
+                "releases": {
+                    "edges": release_edges,
+                    "pageInfo": { "hasNextPage": releases_cursor.is_some(), "endCursor": releases_cursor },
+                    "totalCount": 3
+                },
+                "refs": {
+                    "edges": tag_edges,
+                    "pageInfo": { "hasNextPage": tags_cursor.is_some(), "endCursor": tags_cursor },
+                    "totalCount": 4
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn list_all_modules() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "first: 2, after: null",
+        &search_response(
+            &[("terraform-aws-s3-module", &[]), ("docs", &[])],
+            Some("page-1"),
+        ),
+    )?;
+    fake_gh.respond(
+        "first: 2, after: \"page-1\"",
+        &search_response(&[("terraform-aws-vpc-module", &[])], None),
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--all", "--first", "2", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(response["modules"][0]["name"], "s3");
+    assert_eq!(response["modules"][1]["name"], "vpc");
+    assert_eq!(response["hidden_count"], 1);
+    assert_eq!(
+        response["pagination"]["next_cursor"],
+        serde_json::Value::Null
+    );
+    Ok(())
+}
+
+#[test]
+fn list_modules_up_to_limit() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "first: 2, after: null",
+        &search_response(
+            &[
+                ("terraform-aws-s3-module", &[]),
+                ("terraform-aws-vpc-module", &[]),
+            ],
+            Some("page-1"),
+        ),
+    )?;
+    fake_gh.respond(
+        "first: 1, after: \"page-1\"",
+        &search_response(&[("terraform-aws-iam-module", &[])], Some("page-2")),
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args([
+            "ls", "--org", "org", "--first", "2", "--limit", "3", "--json",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(response["modules"].as_array().map(Vec::len), Some(3));
+    assert_eq!(response["pagination"]["next_cursor"], "page-2");
+    Ok(())
+}
+
+#[test]
+fn list_rejects_zero_limit() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    for args in [
+        &["ls", "--org", "org"][..],
+        &["ls", "aws-s3", "--org", "org"],
+        &["search", "queue", "--org", "org"],
+    ] {
+        fake_gh
+            .command()?
+            .args(args)
+            .args(["--limit", "0"])
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("The limit must be at least 1"));
+    }
+    Ok(())
+}
+
+#[test]
+fn list_all_releases_and_tags() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "releases(first: 2, after: null",
+        &module_page(
+            &["3.0.0", "2.0.0"],
+            Some("releases-1"),
+            &["3.0.0", "2.0.0"],
+            Some("tags-1"),
+        ),
+    )?;
+    fake_gh.respond(
+        "releases(first: 2, after: \"releases-1\"",
+        &module_page(&["1.0.0"], None, &["1.1.0", "1.0.0"], None),
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args([
+            "ls", "aws-s3", "--org", "org", "--all", "--first", "2", "--json",
+        ])
+        .output()?;
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        response["releases"]["items"].as_array().map(Vec::len),
+        Some(3)
+    );
+    assert_eq!(response["tags"]["items"].as_array().map(Vec::len), Some(4));
+    assert_eq!(response["tags"]["items"][3]["name"], "1.0.0");
+    Ok(())
+}
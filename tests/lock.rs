@@ -0,0 +1,105 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh};
+use std::fs;
+
+#[test]
+fn install_records_module_in_lockfile() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.2.0\")",
+        &release_response("terraform-aws-s3-module", "1.2.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.2.0"]))?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "-o", "org"])
+        .assert()
+        .success();
+
+    let lockfile: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(
+        fake_gh.project_file(".gh-tf-mod.lock"),
+    )?)?;
+    let locked_module = &lockfile["modules"]["aws-s3"];
+    assert_eq!(locked_module["repo"], "terraform-aws-s3-module");
+    assert_eq!(locked_module["tag"], "1.2.0");
+    assert_eq!(
+        locked_module["commit"],
+        "0123456789abcdef0123456789abcdef01234567"
+    );
+    assert_eq!(locked_module["sha256"].as_str().map(str::len), Some(64));
+    Ok(())
+}
+
+#[test]
+fn locked_install_fails_when_checksum_changes() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.2.0\")",
+        &release_response("terraform-aws-s3-module", "1.2.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.2.0"]))?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3", "-o", "org"])
+        .assert()
+        .success();
+    fs::remove_dir_all(fake_gh.project_file("modules"))?;
+
+    fake_gh
+        .command()?
+        .args(["install", "--locked"])
+        .assert()
+        .success();
+    assert!(fake_gh.project_file("modules/aws/s3/main.tf").exists());
+
+    let size = fake_gh.release_asset("1.2.0", &[("main.tf", "# tampered")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.2.0\")",
+        &release_response("terraform-aws-s3-module", "1.2.0", size),
+    )?;
+    fake_gh
+        .command()?
+        .args(["install", "--locked"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Checksum"));
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3"
+    );
+    Ok(())
+}
+
+#[test]
+fn locked_install_fails_without_recorded_modules() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nclient: http\n",
+    )?;
+
+    for lockfile in [None, Some(""), Some("---\nmodules: {}\n")] {
+        if let Some(lockfile) = lockfile {
+            fs::write(fake_gh.project_file(".gh-tf-mod.lock"), lockfile)?;
+        }
+        fake_gh
+            .command()?
+            .args(["install", "--locked", "-o", "org"])
+            .env_remove("GH_TOKEN")
+            .env_remove("GITHUB_TOKEN")
+            .env("GH_CONFIG_DIR", fake_gh.project_file("no-gh-config"))
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(
+                "No modules are recorded in .gh-tf-mod.lock",
+            ));
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh, LOCKFILE};
+use std::fs;
+
+const CONFIG: &str = "---
+org: org
+modules:
+  aws-s3:
+    version: ~> 2.1
+  bucket:
+    provider: gcp
+    version: 1.0.0
+    path: infra/bucket
+";
+
+#[test]
+fn install_syncs_working_tree_to_manifest() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(fake_gh.project_file(".config/gh-tf-mod.yaml"), CONFIG)?;
+    fs::write(
+        fake_gh.project_file(".gh-tf-mod.lock"),
+        LOCKFILE
+            .replace("aws-s3:", "aws-vpc:")
+            .replace("name: s3", "name: vpc")
+            .replace("path: modules/aws/s3", "path: modules/aws/vpc"),
+    )?;
+    fs::create_dir_all(fake_gh.project_file("modules/aws/vpc"))?;
+
+    fake_gh.respond("refs(refPrefix", &tags_response(&["2.2.0", "2.1.0"]))?;
+    let size = fake_gh.release_asset("2.2.0", &[("main.tf", "# s3 2.2.0")])?;
+    fake_gh.respond(
+        "release(tagName: \"2.2.0\")",
+        &release_response("terraform-aws-s3-module", "2.2.0", size),
+    )?;
+    let size = fake_gh.release_asset("1.0.0", &[("main.tf", "# bucket 1.0.0")])?;
+    fake_gh.respond(
+        "release(tagName: \"1.0.0\")",
+        &release_response("terraform-gcp-bucket-module", "1.0.0", size),
+    )?;
+
+    fake_gh
+        .command()?
+        .arg("install")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Removed modules/aws/vpc"));
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3 2.2.0"
+    );
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("infra/bucket/main.tf"))?,
+        "# bucket 1.0.0"
+    );
+    let lockfile: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(
+        fake_gh.project_file(".gh-tf-mod.lock"),
+    )?)?;
+    assert_eq!(lockfile["modules"]["aws-s3"]["version"], "~> 2.1");
+    assert_eq!(lockfile["modules"]["gcp-bucket"]["path"], "infra/bucket");
+    assert!(lockfile["modules"]["aws-vpc"].is_null());
+    assert!(!fake_gh.project_file("modules/aws/vpc").exists());
+
+    fake_gh
+        .command()?
+        .arg("install")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "terraform-aws-s3-module 2.2.0 is up to date in modules/aws/s3",
+        ));
+    Ok(())
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, search_response, tags_response, FakeGh};
+use std::fs;
+
+const NAMING_CONFIG: &str = "---
+org: org
+naming:
+  pattern: ^tfmod-(?P<provider>[^-]+)-(?P<name>.+)$
+";
+
+const TOPIC_CONFIG: &str = "---
+org: org
+naming:
+  discovery: topic
+";
+
+#[test]
+fn list_modules_with_naming_pattern() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "tfmod-aws in:name user:org",
+        &search_response(
+            &[
+                ("tfmod-aws-s3", &[]),
+                ("tfmod-aws", &[]),
+                ("terraform-aws-vpc-module", &[]),
+            ],
+            None,
+        ),
+    )?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        NAMING_CONFIG,
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args(["ls", "--provider", "aws", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(response["modules"].as_array().map(Vec::len), Some(1));
+    assert_eq!(response["modules"][0]["provider"], "aws");
+    assert_eq!(response["modules"][0]["name"], "s3");
+    assert_eq!(response["hidden_count"], 2);
+    Ok(())
+}
+
+#[test]
+fn install_with_naming_pattern() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.0.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "repository(name: \"tfmod-aws-s3\"",
+        &release_response("tfmod-aws-s3", "1.0.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.0.0"]))?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        NAMING_CONFIG,
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Installed tfmod-aws-s3 1.0.0"));
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3"
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_naming_pattern() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nnaming:\n  pattern: ^tfmod-(?P<name>.+)$\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "must have `provider` and `name` captures",
+        ));
+    Ok(())
+}
+
+#[test]
+fn list_modules_by_topic() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "topic:terraform-module user:org",
+        &search_response(
+            &[
+                ("aws-s3", &["terraform-module", "provider-aws"]),
+                ("vpc", &["terraform-module", "provider-aws"]),
+                ("docs", &["terraform-module"]),
+            ],
+            None,
+        ),
+    )?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(fake_gh.project_file(".config/gh-tf-mod.yaml"), TOPIC_CONFIG)?;
+
+    let output = fake_gh.command()?.args(["ls", "--json"]).output()?;
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(response["modules"][0]["provider"], "aws");
+    assert_eq!(response["modules"][0]["name"], "s3");
+    assert_eq!(response["modules"][1]["name"], "vpc");
+    assert_eq!(response["hidden_count"], 1);
+    Ok(())
+}
+
+#[test]
+fn install_by_topic() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let size = fake_gh.release_asset("1.0.0", &[("main.tf", "# s3")])?;
+    fake_gh.respond(
+        "s3 in:name topic:terraform-module topic:provider-aws user:org",
+        &search_response(
+            &[
+                ("s3-logs", &["terraform-module", "provider-aws"]),
+                ("aws-s3", &["terraform-module", "provider-aws"]),
+            ],
+            None,
+        ),
+    )?;
+    fake_gh.respond(
+        "repository(name: \"aws-s3\"",
+        &release_response("aws-s3", "1.0.0", size),
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["1.0.0"]))?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(fake_gh.project_file(".config/gh-tf-mod.yaml"), TOPIC_CONFIG)?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Installed aws-s3 1.0.0 to modules/aws/s3",
+        ));
+    Ok(())
+}
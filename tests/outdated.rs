@@ -0,0 +1,84 @@
+mod common;
+
+use anyhow::Result;
+use common::{tags_response, FakeGh, LOCKFILE};
+use std::fs;
+
+#[test]
+fn outdated_reports_wanted_and_latest_versions() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fs::write(fake_gh.project_file(".gh-tf-mod.lock"), LOCKFILE)?;
+    fake_gh.respond(
+        "refs(refPrefix",
+        &tags_response(&["4.0.0-rc.1", "3.0.0", "2.2.0", "2.1.0"]),
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args(["outdated", "--json"])
+        .assert()
+        .code(9)
+        .get_output()
+        .stdout
+        .clone();
+
+    let outdated: serde_json::Value = serde_json::from_slice(&output)?;
+    let outdated = &outdated["modules"];
+    assert_eq!(outdated[0]["current"], "2.1.0");
+    assert_eq!(outdated[0]["wanted"], "2.2.0");
+    assert_eq!(outdated[0]["latest"], "3.0.0");
+    assert_eq!(outdated[0]["outdated"], true);
+    Ok(())
+}
+
+#[test]
+fn outdated_succeeds_when_up_to_date() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fs::write(fake_gh.project_file(".gh-tf-mod.lock"), LOCKFILE)?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["2.1.0", "2.0.0"]))?;
+
+    fake_gh
+        .command()?
+        .args(["outdated", "--no-color"])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn outdated_checks_modules_concurrently_in_lockfile_order() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let lockfile = ["s3", "sqs", "vpc"]
+        .iter()
+        .fold(String::from("---\nmodules:\n"), |lockfile, name| {
+            lockfile + &LOCKFILE.replace("s3", name).replace("---\nmodules:\n", "")
+        });
+    fs::write(fake_gh.project_file(".gh-tf-mod.lock"), lockfile)?;
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nconcurrency: 2\n",
+    )?;
+    fake_gh.respond("refs(refPrefix", &tags_response(&["2.2.0", "2.1.0"]))?;
+
+    let output = fake_gh
+        .command()?
+        .args(["outdated", "--json"])
+        .assert()
+        .code(9)
+        .get_output()
+        .stdout
+        .clone();
+
+    let outdated: serde_json::Value = serde_json::from_slice(&output)?;
+    let outdated = &outdated["modules"];
+    let names: Vec<&str> = outdated
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|module| module["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["s3", "sqs", "vpc"]);
+    assert_eq!(outdated[2]["wanted"], "2.2.0");
+    Ok(())
+}
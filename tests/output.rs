@@ -0,0 +1,126 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+use std::fs;
+
+fn respond_with_modules(fake_gh: &FakeGh) -> Result<()> {
+    let mut response = search_response(
+        &[
+            ("terraform-aws-s3-module", &[]),
+            ("terraform-aws-sqs-module", &[]),
+        ],
+        None,
+    );
+    response["data"]["search"]["nodes"][0]["description"] = "An S3 bucket, | piped".into();
+    response["data"]["search"]["nodes"][0]["refs"]["nodes"] =
+        serde_json::json!([{ "name": "1.2.0" }]);
+    fake_gh.respond("search(", &response)
+}
+
+#[test]
+fn list_as_csv() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--output", "csv", "-d", "-t"])
+        .assert()
+        .success()
+        .stdout(
+            "Name,Provider,Description,Latest Tag\n\
+             s3,aws,\"An S3 bucket, | piped\",1.2.0\n\
+             sqs,aws,,\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn list_as_markdown() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--output", "markdown", "-d"])
+        .assert()
+        .success()
+        .stdout(
+            "| Name | Provider | Description |\n\
+             | --- | --- | --- |\n\
+             | s3 | aws | An S3 bucket, \\| piped |\n\
+             | sqs | aws |  |\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn list_as_yaml_and_json_use_the_module_model() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--output", "yaml"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "- name: s3\n    provider: aws\n    repo: terraform-aws-s3-module\n",
+        ));
+
+    let output = fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--output", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let module_list: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(module_list["modules"][0]["latest_tag"], "1.2.0");
+    assert_eq!(
+        module_list["modules"][1]["repo"],
+        "terraform-aws-sqs-module"
+    );
+    assert_eq!(module_list["pagination"]["total_count"], 2);
+    assert_eq!(
+        module_list["pagination"]["next_cursor"],
+        serde_json::Value::Null
+    );
+    Ok(())
+}
+
+#[test]
+fn list_with_template() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_modules(&fake_gh)?;
+    fs::write(
+        fake_gh.project_file("catalog.hbs"),
+        "{{#each modules}}- [{{provider}}/{{name}}]({{url}})\n{{/each}}",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--output", "template=catalog.hbs"])
+        .assert()
+        .success()
+        .stdout(
+            "- [aws/s3](https://github.com/org/terraform-aws-s3-module)\n\
+             - [aws/sqs](https://github.com/org/terraform-aws-sqs-module)\n",
+        );
+    Ok(())
+}
+
+#[test]
+fn unknown_output_format() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--output", "xml"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Unknown output format xml"));
+    Ok(())
+}
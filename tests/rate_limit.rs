@@ -0,0 +1,144 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+use predicates::prelude::*;
+use std::fs;
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+#[test]
+fn retries_when_rate_limited() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "rateLimit",
+        &search_response(&[("terraform-aws-s3-module", &[])], None),
+    )?;
+    fake_gh.fail_once("graphql", "GraphQL: API rate limit exceeded for user ID 1.")?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("s3"))
+        .stderr(predicates::str::contains(
+            "Rate limited by GitHub, retrying in",
+        ));
+    Ok(())
+}
+
+#[test]
+fn warns_when_rate_limit_is_low() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let mut response = search_response(&[("terraform-aws-s3-module", &[])], None);
+    response["data"]["rateLimit"] = serde_json::json!({
+        "remaining": 42,
+        "resetAt": "2021-01-01T01:00:00Z",
+        "cost": 1
+    });
+    fake_gh.respond("rateLimit", &response)?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "Warning: only 42 points of the GitHub API rate limit remain until 2021-01-01T01:00:00Z",
+        ));
+    Ok(())
+}
+
+#[test]
+fn fails_fast_when_rate_limit_resets_later() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let response = serde_json::json!({
+        "data": {
+            "rateLimit": { "remaining": 0, "resetAt": "2099-01-01T00:00:00Z", "cost": 1 }
+        },
+        "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+    });
+    fake_gh.respond("rateLimit", &response)?;
+
+    fake_gh
+        .command()?
+        .args(["ls", "--org", "org"])
+        .assert()
+        .code(5)
+        .stderr(predicates::str::contains(
+            "Wait for the rate limit to reset at Thu, 01 Jan 2099 00:00:00 GMT",
+        ))
+        .stderr(predicates::str::contains("retrying").not());
+    Ok(())
+}
+
+#[test]
+fn reports_when_rate_limit_resets() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    for (reset_at, expected) in [
+        ("2096-02-29T12:00:00Z", "Wed, 29 Feb 2096 12:00:00 GMT"),
+        ("2096-03-01T00:00:00Z", "Thu, 01 Mar 2096 00:00:00 GMT"),
+        ("2099-12-31T23:59:59Z", "Thu, 31 Dec 2099 23:59:59 GMT"),
+        ("2100-01-01T00:00:00Z", "Fri, 01 Jan 2100 00:00:00 GMT"),
+        ("2100-03-01T00:00:00Z", "Mon, 01 Mar 2100 00:00:00 GMT"),
+        ("2099-12-31T23:59:59.75Z", "Thu, 31 Dec 2099 23:59:59 GMT"),
+    ] {
+        let response = serde_json::json!({
+            "data": {
+                "rateLimit": { "remaining": 0, "resetAt": reset_at, "cost": 1 }
+            },
+            "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+        });
+        fake_gh.respond("rateLimit", &response)?;
+
+        fake_gh
+            .command()?
+            .args(["ls", "--org", "org", "--refresh"])
+            .assert()
+            .code(5)
+            .stderr(predicates::str::contains(format!(
+                "Wait for the rate limit to reset at {}",
+                expected
+            )));
+    }
+    Ok(())
+}
+
+#[test]
+fn waits_for_retry_after() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let server = Server::http("127.0.0.1:0").map_err(|err| anyhow::anyhow!(err))?;
+    let api_url = format!("http://{}", server.server_addr());
+    let search = search_response(&[("terraform-aws-s3-module", &[])], None).to_string();
+    thread::spawn(move || {
+        for (index, request) in server.incoming_requests().enumerate() {
+            let response = match index {
+                0 => Response::from_string("You have exceeded a secondary rate limit.")
+                    .with_status_code(403)
+                    .with_header(Header::from_bytes("Retry-After", "1").unwrap()),
+                _ => Response::from_string(search.clone()),
+            };
+            let _ = request.respond(response);
+        }
+    });
+    fs::create_dir_all(fake_gh.project_file(".config"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod.yaml"),
+        "---\norg: org\nclient: http\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["ls"])
+        .env("GITHUB_API_URL", &api_url)
+        .env("GH_TOKEN", "test-token")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("s3"))
+        .stderr(predicates::str::contains(
+            "Rate limited by GitHub, retrying in",
+        ));
+    Ok(())
+}
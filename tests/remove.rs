@@ -0,0 +1,123 @@
+mod common;
+
+use anyhow::Result;
+use common::{FakeGh, LOCKFILE};
+use predicates::prelude::*;
+use std::fs;
+
+fn vendor_module(fake_gh: &FakeGh) -> Result<()> {
+    fs::create_dir_all(fake_gh.project_file("modules/aws/s3"))?;
+    fs::write(fake_gh.project_file("modules/aws/s3/main.tf"), "# s3")?;
+    Ok(())
+}
+
+#[test]
+fn remove_deletes_module() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    vendor_module(&fake_gh)?;
+
+    fake_gh.command()?.args(["rm", "aws-s3"]).assert().success();
+
+    assert!(!fake_gh.project_file("modules/aws/s3").exists());
+    Ok(())
+}
+
+#[test]
+fn remove_refuses_referenced_module_unless_forced() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    vendor_module(&fake_gh)?;
+    fs::create_dir_all(fake_gh.project_file("envs/prod"))?;
+    fs::write(
+        fake_gh.project_file("envs/prod/main.tf"),
+        "module \"bucket\" {\n  source = \"../../modules/aws/s3\"\n}\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["rm", "aws-s3"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("module \"bucket\""));
+    assert!(fake_gh.project_file("modules/aws/s3").exists());
+
+    fake_gh
+        .command()?
+        .args(["rm", "s3", "-p", "aws", "--force"])
+        .assert()
+        .success();
+    assert!(!fake_gh.project_file("modules/aws/s3").exists());
+    Ok(())
+}
+
+#[test]
+fn remove_refuses_module_referenced_after_nested_map() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    vendor_module(&fake_gh)?;
+    fs::write(
+        fake_gh.project_file("main.tf"),
+        r#"
+# module "commented" {
+#   source = "./modules/aws/s3"
+# }
+
+module "bucket" {
+  providers = { aws = aws.east }
+  source    = "./modules/aws/s3"
+}
+"#,
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["rm", "aws-s3"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("module \"bucket\""))
+        .stderr(predicates::str::contains("module \"commented\"").not());
+    assert!(fake_gh.project_file("modules/aws/s3").exists());
+    Ok(())
+}
+
+#[test]
+fn remove_ignores_commented_out_references() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    vendor_module(&fake_gh)?;
+    fs::write(
+        fake_gh.project_file("main.tf"),
+        "/*\nmodule \"bucket\" {\n  source = \"./modules/aws/s3\"\n}\n*/\n",
+    )?;
+
+    fake_gh.command()?.args(["rm", "aws-s3"]).assert().success();
+    assert!(!fake_gh.project_file("modules/aws/s3").exists());
+    Ok(())
+}
+
+#[test]
+fn remove_forgets_locked_module_deleted_by_hand() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fs::write(fake_gh.project_file(".gh-tf-mod.lock"), LOCKFILE)?;
+
+    fake_gh
+        .command()?
+        .args(["rm", "aws-s3"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "Warning: No module is installed in modules/aws/s3, removing aws-s3 from .gh-tf-mod.lock only",
+        ));
+
+    let lockfile: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(
+        fake_gh.project_file(".gh-tf-mod.lock"),
+    )?)?;
+    assert!(lockfile["modules"]["aws-s3"].is_null());
+
+    fake_gh
+        .command()?
+        .args(["rm", "aws-s3"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "No module is installed in modules/aws/s3",
+        ));
+    Ok(())
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+
+#[test]
+fn schema_describes_every_document() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    let output = fake_gh
+        .command()?
+        .args(["schema"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let schema: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(schema["title"], "gh-tf-mod output, schema version 1");
+    assert_eq!(schema["anyOf"].as_array().map(Vec::len), Some(4));
+    assert!(schema["definitions"]["Module"].is_object());
+    Ok(())
+}
+
+#[test]
+fn schema_of_one_kind() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    let output = fake_gh
+        .command()?
+        .args(["schema", "outdated"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let schema: serde_json::Value = serde_json::from_slice(&output)?;
+    assert!(schema["properties"]["schema_version"].is_object());
+    assert!(schema["properties"]["modules"].is_object());
+    assert!(schema["properties"]["warnings"].is_object());
+    Ok(())
+}
+
+#[test]
+fn json_output_is_versioned() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "search(",
+        &search_response(&[("terraform-aws-s3-module", &[])], None),
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args(["ls", "--org", "org", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let document: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(document["schema_version"], 1);
+    assert_eq!(document["kind"], "module_list");
+    assert_eq!(document["warnings"], serde_json::json!([]));
+    assert_eq!(document["modules"][0]["name"], "s3");
+    Ok(())
+}
@@ -0,0 +1,176 @@
+mod common;
+
+use anyhow::Result;
+use common::{search_response, FakeGh};
+
+fn respond_with_results(fake_gh: &FakeGh, pattern: &str) -> Result<()> {
+    let mut response = search_response(
+        &[
+            ("terraform-aws-lambda-module", &[]),
+            ("docs", &[]),
+            ("terraform-aws-sqs-module", &[]),
+        ],
+        None,
+    );
+    let nodes = &mut response["data"]["search"]["nodes"];
+    nodes[0]["description"] = "Consumes an SQS queue".into();
+    nodes[2]["description"] = "An SQS queue with a DLQ".into();
+    nodes[2]["releases"]["nodes"] = serde_json::json!([{
+        "name": "1.0.0",
+        "tagName": "1.0.0",
+        "publishedAt": "2024-02-01T00:00:00Z"
+    }]);
+    fake_gh.respond(pattern, &response)
+}
+
+fn module_names(output: &[u8]) -> Result<Vec<String>> {
+    let module_list: serde_json::Value = serde_json::from_slice(output)?;
+    Ok(module_list["modules"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|module| module["name"].as_str().unwrap_or_default().to_string())
+        .collect())
+}
+
+#[test]
+fn search_ranks_modules_matching_by_name_first() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_results(
+        &fake_gh,
+        "sqs dlq in:name,description,topics,readme user:org pushed:>=2024-01-31 archived:false",
+    )?;
+
+    let output = fake_gh
+        .command()?
+        .args([
+            "search",
+            "sqs dlq",
+            "--org",
+            "org",
+            "--updated-since",
+            "2024-01-31",
+            "--archived=false",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(module_names(&output)?, ["sqs", "lambda"]);
+    let module_list: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(module_list["hidden_count"], 1);
+    Ok(())
+}
+
+#[test]
+fn search_modules_with_a_release() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_results(&fake_gh, "queue in:name,description,topics,readme")?;
+
+    let output = fake_gh
+        .command()?
+        .args(["search", "queue", "--org", "org", "--has-release", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(module_names(&output)?, ["sqs"]);
+    Ok(())
+}
+
+#[test]
+fn search_phrase_in_table() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_results(
+        &fake_gh,
+        "\\\"dead letter\\\" in:name,description,topics,readme",
+    )?;
+
+    fake_gh
+        .command()?
+        .args([
+            "search",
+            "\"dead letter\"",
+            "--org",
+            "org",
+            "-d",
+            "--no-color",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("An SQS queue with a DLQ"))
+        .stdout(predicates::str::contains("Consumes an SQS queue"));
+    Ok(())
+}
+
+fn with_release(response: &mut serde_json::Value, index: usize) {
+    response["data"]["search"]["nodes"][index]["releases"]["nodes"] = serde_json::json!([{
+        "name": "1.0.0",
+        "tagName": "1.0.0",
+        "publishedAt": "2024-02-01T00:00:00Z"
+    }]);
+}
+
+#[test]
+fn search_limit_counts_modules_with_a_release_across_pages() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    let mut first_page = search_response(
+        &[
+            ("terraform-aws-lambda-module", &[]),
+            ("terraform-aws-sqs-module", &[]),
+        ],
+        Some("page-1"),
+    );
+    with_release(&mut first_page, 1);
+    fake_gh.respond("first: 2, after: null", &first_page)?;
+    let mut second_page = search_response(&[("terraform-aws-sns-module", &[])], Some("page-2"));
+    with_release(&mut second_page, 0);
+    fake_gh.respond("first: 1, after: \"page-1\"", &second_page)?;
+
+    let output = fake_gh
+        .command()?
+        .args([
+            "search",
+            "queue",
+            "--org",
+            "org",
+            "--has-release",
+            "--first",
+            "10",
+            "--limit",
+            "2",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    assert_eq!(module_names(&output)?, ["sqs", "sns"]);
+    let module_list: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(module_list["hidden_count"], 1);
+    assert_eq!(module_list["pagination"]["next_cursor"], "page-2");
+    Ok(())
+}
+
+#[test]
+fn search_rejects_updated_since_that_is_not_a_date() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+
+    for updated_since in ["2024-1-31", "2024-02-30", "last week"] {
+        fake_gh
+            .command()?
+            .args(["search", "queue", "--org", "org"])
+            .args(["--updated-since", updated_since])
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("expected YYYY-MM-DD"));
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, FakeGh};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Stdio};
+
+fn releases_response(tags: &[&str]) -> serde_json::Value {
+    let release_edges: Vec<serde_json::Value> = tags
+        .iter()
+        .map(
+            |tag| serde_json::json!({ "node": { "name": tag, "url": "", "tag": { "name": tag } } }),
+        )
+        .collect();
+    serde_json::json!({
+        "data": {
+            "repository": {
+                "name": "terraform-aws-s3-module",
+                "description": null,
+                "url": "https://github.com/org/terraform-aws-s3-module",
+                "releases": {
+                    "edges": release_edges,
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": tags.len()
+                },
+                "refs": {
+                    "edges": [],
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "totalCount": 0
+                }
+            }
+        }
+    })
+}
+
+/// The `serve` process, killed when dropped.
+struct Registry {
+    child: Child,
+    url: String,
+}
+
+impl Registry {
+    fn start(fake_gh: &FakeGh) -> Result<Registry> {
+        let mut child = fake_gh
+            .std_command()?
+            .args(["serve", "--addr", "127.0.0.1:0"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap()).read_line(&mut line)?;
+        let url = line
+            .trim()
+            .rsplit(' ')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Ok(Registry { child, url })
+    }
+
+    fn get(&self, path: &str) -> ureq::Response {
+        match ureq::get(&format!("{}{}", self.url, path)).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl Drop for Registry {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn serve_discovery_versions_and_download() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "releases(first: 100",
+        &releases_response(&["v2.0.0", "1.0.0", "not-a-version"]),
+    )?;
+    fake_gh.respond(
+        "release(tagName: \"v2.0.0\")",
+        &release_response("terraform-aws-s3-module", "v2.0.0", 0),
+    )?;
+    let registry = Registry::start(&fake_gh)?;
+
+    let discovery: serde_json::Value = registry.get("/.well-known/terraform.json").into_json()?;
+    assert_eq!(discovery["modules.v1"], "/v1/modules/");
+
+    let versions: serde_json::Value = registry
+        .get("/v1/modules/org/s3/aws/versions")
+        .into_json()?;
+    assert_eq!(
+        versions["modules"][0]["versions"],
+        serde_json::json!([{ "version": "2.0.0" }, { "version": "1.0.0" }])
+    );
+
+    let download = registry.get("/v1/modules/org/s3/aws/2.0.0/download");
+    assert_eq!(download.status(), 204);
+    assert_eq!(
+        download.header("X-Terraform-Get"),
+        Some("https://github.com/org/terraform-aws-s3-module/releases/download/v2.0.0/release.tar.gz")
+    );
+    Ok(())
+}
+
+#[test]
+fn serve_unknown_versions_and_modules() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond("releases(first: 100", &releases_response(&["1.0.0"]))?;
+    let registry = Registry::start(&fake_gh)?;
+
+    let download = registry.get("/v1/modules/org/s3/aws/3.0.0/download");
+    assert_eq!(download.status(), 404);
+    let error: serde_json::Value = download.into_json()?;
+    assert_eq!(error["errors"][0], "Version 3.0.0 not found for s3");
+
+    assert_eq!(registry.get("/v1/modules/org/s3").status(), 404);
+    Ok(())
+}
+
+#[test]
+fn serve_rejects_segments_that_are_not_names() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond("releases(first: 100", &releases_response(&["1.0.0"]))?;
+    let registry = Registry::start(&fake_gh)?;
+
+    assert_eq!(
+        registry.get("/v1/modules/org/s3/aws/versions").status(),
+        200
+    );
+    for path in [
+        "/v1/modules/org\"){x}/s3/aws/versions",
+        "/v1/modules/org/s3%22/aws/versions",
+        "/v1/modules/org/s3/aws{/1.0.0/download",
+    ] {
+        let response = registry.get(path);
+        assert_eq!(response.status(), 404, "{}", path);
+        let error: serde_json::Value = response.into_json()?;
+        assert_eq!(error["errors"][0], "Not Found");
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use anyhow::Result;
+use common::{files_response, FakeGh};
+use predicates::prelude::*;
+
+fn module_response(readme: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "data": {
+            "repository": {
+                "name": "terraform-aws-s3-module",
+                "description": "An S3 bucket",
+                "url": "https://github.com/org/terraform-aws-s3-module",
+                "readme": readme.map(|text| serde_json::json!({ "text": text }))
+            }
+        }
+    })
+}
+
+#[test]
+fn show_prints_raw_readme_at_version() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "1.2.0:README.md",
+        &module_response(Some("# S3\n\nCreates a **bucket**.\n")),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["show", "aws-s3@1.2.0", "--org", "org", "--raw"])
+        .assert()
+        .success()
+        .stdout("# S3\n\nCreates a **bucket**.\n");
+    Ok(())
+}
+
+#[test]
+fn show_renders_readme_of_default_branch() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "HEAD:README.md",
+        &module_response(Some("# S3\n\nCreates a **bucket**.\n")),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["show", "aws-s3", "--org", "org", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "terraform-aws-s3-module (HEAD)\nAn S3 bucket\nhttps://github.com/org/terraform-aws-s3-module\n",
+        ))
+        .stdout(predicates::str::contains("Creates a bucket."));
+    Ok(())
+}
+
+#[test]
+fn show_fails_without_readme() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond("HEAD:README.md", &module_response(None))?;
+
+    fake_gh
+        .command()?
+        .args(["show", "aws-s3", "--org", "org"])
+        .assert()
+        .code(4)
+        .stderr(predicates::str::contains(
+            "No README.md found in terraform-aws-s3-module at HEAD",
+        ));
+    Ok(())
+}
+
+const VARIABLES_TF: &str = r#"
+variable "bucket" {
+  type        = string
+  description = "Name of the bucket"
+
+  validation {
+    condition     = length(var.bucket) > 3
+    error_message = "The bucket name is too short."
+  }
+}
+
+variable "tags" {
+  type      = map(string)
+  default   = { team = "platform" }
+  sensitive = true
+}
+"#;
+
+const OUTPUTS_TF: &str = r#"
+output "arn" {
+  value       = aws_s3_bucket.this.arn
+  description = "ARN of the bucket"
+}
+"#;
+
+fn module_files_response() -> serde_json::Value {
+    files_response(
+        "terraform-aws-s3-module",
+        &[
+            ("README.md", "# S3\n"),
+            ("outputs.tf", OUTPUTS_TF),
+            ("variables.tf", VARIABLES_TF),
+        ],
+    )
+}
+
+#[test]
+fn show_inputs_and_outputs_as_json() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond("2.0.0:", &module_files_response())?;
+
+    let output = fake_gh
+        .command()?
+        .args([
+            "show",
+            "aws-s3@2.0.0",
+            "--org",
+            "org",
+            "--inputs",
+            "--outputs",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let interface: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(interface["inputs"][0]["name"], "bucket");
+    assert_eq!(interface["inputs"][0]["type"], "string");
+    assert_eq!(interface["inputs"][0]["required"], true);
+    assert_eq!(
+        interface["inputs"][0]["validations"][0]["error_message"],
+        "The bucket name is too short."
+    );
+    assert_eq!(interface["inputs"][1]["type"], "map(string)");
+    assert_eq!(interface["inputs"][1]["default"]["team"], "platform");
+    assert_eq!(interface["inputs"][1]["sensitive"], true);
+    assert_eq!(interface["outputs"][0]["name"], "arn");
+    assert_eq!(interface["outputs"][0]["description"], "ARN of the bucket");
+    Ok(())
+}
+
+#[test]
+fn show_inputs_table() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond("HEAD:", &module_files_response())?;
+
+    fake_gh
+        .command()?
+        .args(["show", "aws-s3", "--org", "org", "--inputs", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Name of the bucket"))
+        .stdout(predicates::str::contains("{ \"team\" = \"platform\" }"))
+        .stdout(predicates::str::contains("ARN of the bucket").not());
+    Ok(())
+}
+
+#[test]
+fn show_inputs_csv_has_table_columns() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond("HEAD:", &module_files_response())?;
+
+    fake_gh
+        .command()?
+        .args(["show", "aws-s3", "--org", "org", "--inputs", "--no-color"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "| Input  | Type        | Default                 | Required | Description        | Sensitive | Validation                    |",
+        ));
+    fake_gh
+        .command()?
+        .args(["show", "aws-s3", "--org", "org", "--inputs"])
+        .args(["--output", "csv"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(
+            "Input,Type,Default,Required,Description,Sensitive,Validation\n\
+             bucket,string,,true,Name of the bucket,false,The bucket name is too short.\n",
+        ));
+    Ok(())
+}
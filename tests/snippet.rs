@@ -0,0 +1,171 @@
+mod common;
+
+use anyhow::Result;
+use common::{files_response, tags_response, FakeGh};
+use std::fs;
+
+const VARIABLES_TF: &str = r#"
+variable "bucket" {
+  type        = string
+  description = "Name of the bucket"
+}
+
+variable "lifecycle_rules" {
+  type = list(object({ days = number }))
+}
+
+variable "tags" {
+  type    = map(string)
+  default = {}
+}
+"#;
+
+fn respond_with_module(fake_gh: &FakeGh, tag: &str) -> Result<()> {
+    fake_gh.respond(
+        &format!("{}:", tag),
+        &files_response("terraform-aws-s3-module", &[("variables.tf", VARIABLES_TF)]),
+    )
+}
+
+#[test]
+fn snippet_pins_highest_release_with_https_source() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "refs(refPrefix",
+        &tags_response(&["2.0.0-rc.1", "1.2.0", "1.1.0"]),
+    )?;
+    respond_with_module(&fake_gh, "1.2.0")?;
+
+    fake_gh
+        .command()?
+        .args(["snippet", "aws-s3", "--org", "org"])
+        .assert()
+        .success()
+        .stdout(
+            r#"module "s3" {
+  source = "git::https://github.com/org/terraform-aws-s3-module.git?ref=1.2.0"
+
+  # Name of the bucket
+  bucket = ""
+
+  lifecycle_rules = []
+}
+"#,
+        );
+    Ok(())
+}
+
+#[test]
+fn snippet_with_ssh_source() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_module(&fake_gh, "1.1.0")?;
+
+    fake_gh
+        .command()?
+        .args(["snippet", "aws-s3@1.1.0", "--org", "org", "--source", "ssh"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            r#"source = "git::ssh://git@github.com/org/terraform-aws-s3-module.git?ref=1.1.0""#,
+        ));
+    Ok(())
+}
+
+#[test]
+fn snippet_with_path_source() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_module(&fake_gh, "1.1.0")?;
+
+    fake_gh
+        .command()?
+        .args([
+            "snippet",
+            "aws-s3@1.1.0",
+            "--org",
+            "org",
+            "--source",
+            "path",
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            r#"source = "./modules/aws/s3" # 1.1.0"#,
+        ));
+    Ok(())
+}
+
+#[test]
+fn snippet_with_overridden_template() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    respond_with_module(&fake_gh, "1.1.0")?;
+    fs::create_dir_all(fake_gh.project_file(".config/gh-tf-mod/templates"))?;
+    fs::write(
+        fake_gh.project_file(".config/gh-tf-mod/templates/snippet.hbs"),
+        "{{repo}}@{{tag}}:{{#each inputs}} {{name}}{{/each}}\n",
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["snippet", "aws-s3@1.1.0", "--org", "org"])
+        .assert()
+        .success()
+        .stdout("terraform-aws-s3-module@1.1.0: bucket lifecycle_rules\n");
+
+    fs::write(fake_gh.project_file("custom.hbs"), "{{source}}\n")?;
+    fake_gh
+        .command()?
+        .args([
+            "snippet",
+            "aws-s3@1.1.0",
+            "--org",
+            "org",
+            "--template",
+            "custom.hbs",
+        ])
+        .assert()
+        .success()
+        .stdout("git::https://github.com/org/terraform-aws-s3-module.git?ref=1.1.0\n");
+    Ok(())
+}
+
+#[test]
+fn snippet_comments_every_line_of_description() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "1.2.0:",
+        &files_response(
+            "terraform-aws-s3-module",
+            &[(
+                "variables.tf",
+                r#"
+variable "bucket" {
+  type        = string
+  description = <<-EOT
+    Name of the bucket.
+
+    Must be globally unique.
+  EOT
+}
+"#,
+            )],
+        ),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["snippet", "aws-s3@1.2.0", "--org", "org"])
+        .assert()
+        .success()
+        .stdout(
+            r#"module "s3" {
+  source = "git::https://github.com/org/terraform-aws-s3-module.git?ref=1.2.0"
+
+  # Name of the bucket.
+  #
+  # Must be globally unique.
+  bucket = ""
+}
+"#,
+        );
+    Ok(())
+}
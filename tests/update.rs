@@ -0,0 +1,95 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh, LOCKFILE};
+use std::fs;
+
+fn publish_releases(fake_gh: &FakeGh) -> Result<()> {
+    fs::write(fake_gh.project_file(".gh-tf-mod.lock"), LOCKFILE)?;
+    fake_gh.respond(
+        "refs(refPrefix",
+        &tags_response(&["3.0.0", "2.2.0", "2.1.0"]),
+    )?;
+    for tag in ["3.0.0", "2.2.0"] {
+        let size = fake_gh.release_asset(tag, &[("main.tf", &format!("# s3 {}", tag))])?;
+        fake_gh.respond(
+            &format!("release(tagName: \"{}\")", tag),
+            &release_response("terraform-aws-s3-module", tag, size),
+        )?;
+    }
+    Ok(())
+}
+
+fn locked_module(fake_gh: &FakeGh) -> Result<serde_yaml::Value> {
+    let lockfile: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(
+        fake_gh.project_file(".gh-tf-mod.lock"),
+    )?)?;
+    Ok(lockfile["modules"]["aws-s3"].clone())
+}
+
+#[test]
+fn update_stays_within_constraint() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    publish_releases(&fake_gh)?;
+
+    fake_gh
+        .command()?
+        .args(["update", "--dry-run"])
+        .assert()
+        .success()
+        .stdout("Would update aws-s3 from 2.1.0 to 2.2.0\n");
+    assert_eq!(locked_module(&fake_gh)?["tag"], "2.1.0");
+
+    fake_gh
+        .command()?
+        .args(["update", "aws-s3"])
+        .assert()
+        .success()
+        .stdout("Updated aws-s3 from 2.1.0 to 2.2.0\n");
+    assert_eq!(locked_module(&fake_gh)?["tag"], "2.2.0");
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3 2.2.0"
+    );
+    Ok(())
+}
+
+#[test]
+fn update_major_crosses_major_versions() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    publish_releases(&fake_gh)?;
+
+    fake_gh
+        .command()?
+        .args(["update", "--major"])
+        .assert()
+        .success()
+        .stdout("Updated aws-s3 from 2.1.0 to 3.0.0\n");
+
+    let locked_module = locked_module(&fake_gh)?;
+    assert_eq!(locked_module["tag"], "3.0.0");
+    assert_eq!(locked_module["version"], "~> 3.0");
+    Ok(())
+}
+
+#[test]
+fn update_major_leaves_pinned_modules_alone() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    publish_releases(&fake_gh)?;
+    fs::write(
+        fake_gh.project_file(".gh-tf-mod.lock"),
+        LOCKFILE.replace("version: ~> 2.1", "version: 2.1.0"),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["update", "--major"])
+        .assert()
+        .success()
+        .stdout("All modules are up to date\n");
+
+    let locked_module = locked_module(&fake_gh)?;
+    assert_eq!(locked_module["tag"], "2.1.0");
+    assert_eq!(locked_module["version"], "2.1.0");
+    Ok(())
+}
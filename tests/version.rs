@@ -0,0 +1,71 @@
+mod common;
+
+use anyhow::Result;
+use common::{release_response, tags_response, FakeGh};
+use std::fs;
+
+#[test]
+fn install_resolves_highest_matching_version() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "refs(refPrefix",
+        &tags_response(&[
+            "3.0.0",
+            "2.2.0-beta.1",
+            "2.1.4",
+            "v2.1.10",
+            "2.0.0",
+            "latest",
+        ]),
+    )?;
+    let size = fake_gh.release_asset("v2.1.10", &[("main.tf", "# s3 2.1.10")])?;
+    fake_gh.respond(
+        "release(tagName: \"v2.1.10\")",
+        &release_response("terraform-aws-s3-module", "v2.1.10", size),
+    )?;
+
+    fake_gh
+        .command()?
+        .args(["install", "aws-s3@~> 2.1", "-o", "org"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("not valid semver: latest"));
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3 2.1.10"
+    );
+    Ok(())
+}
+
+#[test]
+fn install_considers_prereleases_when_asked() -> Result<()> {
+    let fake_gh = FakeGh::new()?;
+    fake_gh.respond(
+        "refs(refPrefix",
+        &tags_response(&["3.0.0", "2.2.0-beta.1", "2.1.4"]),
+    )?;
+    let size = fake_gh.release_asset("2.2.0-beta.1", &[("main.tf", "# s3 beta")])?;
+    fake_gh.respond(
+        "release(tagName: \"2.2.0-beta.1\")",
+        &release_response("terraform-aws-s3-module", "2.2.0-beta.1", size),
+    )?;
+
+    fake_gh
+        .command()?
+        .args([
+            "install",
+            "aws-s3@>= 1.0, < 3.0",
+            "-o",
+            "org",
+            "--prerelease",
+        ])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(fake_gh.project_file("modules/aws/s3/main.tf"))?,
+        "# s3 beta"
+    );
+    Ok(())
+}